@@ -0,0 +1,401 @@
+//! Accessible name and description computation, following https://www.w3.org/TR/accname-1.2/
+//! Every query that compares against what a screen reader would announce should go through here.
+use crate::cast;
+use crate::label::labels_of;
+use crate::query_root::element_by_id;
+use crate::role;
+use web_sys::{Element, Node};
+
+/// Roles whose accessible name is computed from their content.
+const NAME_FROM_CONTENT_ROLES: &[&str] = &[
+    "button",
+    "cell",
+    "checkbox",
+    "columnheader",
+    "gridcell",
+    "heading",
+    "link",
+    "menuitem",
+    "menuitemcheckbox",
+    "menuitemradio",
+    "option",
+    "radio",
+    "row",
+    "rowheader",
+    "switch",
+    "tab",
+    "tooltip",
+    "treeitem",
+];
+
+/// Roles of controls whose value is used when they're embedded inside the label of another widget.
+const EMBEDDED_CONTROL_ROLES: &[&str] = &[
+    "textbox",
+    "searchbox",
+    "combobox",
+    "listbox",
+    "slider",
+    "spinbutton",
+    "progressbar",
+    "scrollbar",
+];
+
+/// The accessible name of the element, whitespace collapsed.
+pub(crate) fn accessible_name(element: &Element) -> String {
+    let traversal = Traversal {
+        root: element.clone(),
+    };
+    collapse_whitespace(&traversal.compute(element, Context::root()))
+}
+
+/// The accessible description of the element from aria-describedby, aria-description,
+/// or a title attribute that wasn't already used as the name.
+pub(crate) fn accessible_description(element: &Element) -> String {
+    let traversal = Traversal {
+        root: element.clone(),
+    };
+    if let Some(ids) = element.get_attribute("aria-describedby") {
+        let description = traversal.compute_referenced(element, &ids);
+        if !description.trim().is_empty() {
+            return collapse_whitespace(&description);
+        }
+    }
+    if let Some(description) = non_empty_attribute(element, "aria-description") {
+        return collapse_whitespace(&description);
+    }
+    if let Some(title) = non_empty_attribute(element, "title") {
+        let title = collapse_whitespace(&title);
+        if accessible_name(element) != title {
+            return title;
+        }
+    }
+    String::new()
+}
+
+#[derive(Clone, Copy)]
+struct Context {
+    /// The node whose name we are computing, as opposed to a node we recursed into.
+    is_root: bool,
+    /// We got here by following aria-labelledby or aria-describedby, these aren't followed twice.
+    in_labelled_by: bool,
+    /// The node referenced by aria-labelledby was hidden, so hidden content contributes to the name.
+    include_hidden: bool,
+}
+
+impl Context {
+    fn root() -> Self {
+        Self {
+            is_root: true,
+            in_labelled_by: false,
+            include_hidden: false,
+        }
+    }
+    fn recurse(self) -> Self {
+        Self {
+            is_root: false,
+            ..self
+        }
+    }
+}
+
+struct Traversal {
+    root: Element,
+}
+
+impl Traversal {
+    fn compute(&self, node: &Node, ctx: Context) -> String {
+        if node.node_type() == Node::TEXT_NODE {
+            return node.text_content().unwrap_or_default();
+        }
+        let Some(element) = cast::element(node) else {
+            return String::new();
+        };
+        // 2A: hidden nodes don't contribute unless we were pointed at them directly.
+        if !ctx.is_root && !ctx.include_hidden && role::is_hidden(element) {
+            return String::new();
+        }
+        // 2B: aria-labelledby
+        if !ctx.in_labelled_by {
+            if let Some(ids) = element.get_attribute("aria-labelledby") {
+                let name = self.compute_referenced(element, &ids);
+                if !name.trim().is_empty() {
+                    return name;
+                }
+            }
+        }
+        let role = role::element_role(element).unwrap_or_default();
+        let is_embedded_control = !ctx.is_root && EMBEDDED_CONTROL_ROLES.contains(&role.as_str());
+        // 2C: aria-label, embedded controls use their value instead.
+        if !is_embedded_control {
+            if let Some(label) = non_empty_attribute(element, "aria-label") {
+                return label;
+            }
+        }
+        // 2D: native host language labels.
+        if role != "none" && role != "presentation" {
+            if let Some(name) = self.native_name(element, ctx) {
+                if !name.trim().is_empty() {
+                    return name;
+                }
+            }
+        }
+        // 2E: embedded controls
+        if is_embedded_control {
+            return embedded_control_value(element, &role);
+        }
+        // 2F: name from content
+        if !ctx.is_root
+            || NAME_FROM_CONTENT_ROLES.contains(&role.as_str())
+            || element.tag_name().eq_ignore_ascii_case("label")
+        {
+            let content = self.content(element, ctx);
+            if !content.trim().is_empty() {
+                return content;
+            }
+        }
+        // 2I: tooltip attribute, and placeholders as the last resort for text fields.
+        if let Some(title) = non_empty_attribute(element, "title") {
+            return title;
+        }
+        if ctx.is_root && (element.tag_name() == "INPUT" || element.tag_name() == "TEXTAREA") {
+            if let Some(placeholder) = non_empty_attribute(element, "placeholder") {
+                return placeholder;
+            }
+        }
+        String::new()
+    }
+
+    /// The joined names of the elements referenced by an id list, i.e aria-labelledby="first last".
+    fn compute_referenced(&self, element: &Element, ids: &str) -> String {
+        ids.split_whitespace()
+            .filter_map(|id| element_by_id(element, id))
+            .map(|referenced| {
+                let ctx = Context {
+                    is_root: false,
+                    in_labelled_by: true,
+                    include_hidden: role::is_hidden(&referenced),
+                };
+                self.compute(&referenced, ctx).trim().to_string()
+            })
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn native_name(&self, element: &Element, ctx: Context) -> Option<String> {
+        let tag = element.tag_name().to_lowercase();
+        match tag.as_str() {
+            "input" => {
+                let input_type = element
+                    .get_attribute("type")
+                    .unwrap_or_default()
+                    .to_lowercase();
+                match input_type.as_str() {
+                    "button" | "submit" | "reset" => {
+                        let value = non_empty_attribute(element, "value");
+                        value.or_else(|| match input_type.as_str() {
+                            "submit" => Some(String::from("Submit")),
+                            "reset" => Some(String::from("Reset")),
+                            _ => None,
+                        })
+                    }
+                    "image" => non_empty_attribute(element, "alt")
+                        .or_else(|| non_empty_attribute(element, "value"))
+                        .or_else(|| non_empty_attribute(element, "title"))
+                        .or_else(|| Some(String::from("Submit Query"))),
+                    _ => self.label_name(element, ctx),
+                }
+            }
+            "textarea" | "select" | "button" | "meter" | "progress" | "output" => {
+                self.label_name(element, ctx)
+            }
+            "img" | "area" => non_empty_attribute(element, "alt"),
+            "optgroup" => non_empty_attribute(element, "label"),
+            "fieldset" => self.child_name(element, "legend", ctx),
+            "figure" => self.child_name(element, "figcaption", ctx),
+            "table" => self.child_name(element, "caption", ctx),
+            "svg" => self.child_name(element, "title", ctx),
+            _ => None,
+        }
+    }
+
+    /// The joined content of every label element associated with a labelable element.
+    fn label_name(&self, element: &Element, ctx: Context) -> Option<String> {
+        let labels = labels_of(element);
+        if labels.is_empty() {
+            return None;
+        }
+        Some(
+            labels
+                .iter()
+                .map(|label| self.content(label, ctx.recurse()).trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    fn child_name(&self, element: &Element, tag: &str, ctx: Context) -> Option<String> {
+        let children = element.children();
+        (0..children.length())
+            .filter_map(|i| children.item(i))
+            .find(|child| child.tag_name().eq_ignore_ascii_case(tag))
+            .map(|child| self.content(&child, ctx.recurse()))
+    }
+
+    /// Name from content, including CSS generated content.
+    /// Block level children are separated by spaces the way they'd be rendered.
+    fn content(&self, element: &Element, ctx: Context) -> String {
+        let mut content = pseudo_content(element, "::before");
+        let children = element.child_nodes();
+        for i in 0..children.length() {
+            let Some(child) = children.item(i) else {
+                continue;
+            };
+            if let Some(child_element) = cast::element(&child) {
+                // The control a label wraps doesn't name itself.
+                if child_element.is_same_node(Some(&self.root)) {
+                    continue;
+                }
+                let name = self.compute(&child, ctx.recurse());
+                if is_inline(child_element) {
+                    content.push_str(&name);
+                } else {
+                    content.push(' ');
+                    content.push_str(&name);
+                    content.push(' ');
+                }
+            } else {
+                content.push_str(&self.compute(&child, ctx.recurse()));
+            }
+        }
+        content.push_str(&pseudo_content(element, "::after"));
+        content
+    }
+}
+
+fn embedded_control_value(element: &Element, role: &str) -> String {
+    match role {
+        "textbox" | "searchbox" => {
+            if let Some(input) = cast::html_tag::<web_sys::HtmlInputElement>(element, "input") {
+                input.value()
+            } else if let Some(textarea) =
+                cast::html_tag::<web_sys::HtmlTextAreaElement>(element, "textarea")
+            {
+                textarea.value()
+            } else {
+                element.text_content().unwrap_or_default()
+            }
+        }
+        "combobox" | "listbox" => {
+            if let Some(select) = cast::html_tag::<web_sys::HtmlSelectElement>(element, "select") {
+                let options = select.selected_options();
+                return (0..options.length())
+                    .filter_map(|i| options.item(i))
+                    .filter_map(|option| option.text_content())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+            }
+            if let Some(input) = cast::html_tag::<web_sys::HtmlInputElement>(element, "input") {
+                return input.value();
+            }
+            element
+                .query_selector_all("[aria-selected='true']")
+                .map(|selected| {
+                    (0..selected.length())
+                        .filter_map(|i| selected.item(i))
+                        .filter_map(|option| option.text_content())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .unwrap_or_default()
+        }
+        _ => non_empty_attribute(element, "aria-valuetext")
+            .or_else(|| non_empty_attribute(element, "aria-valuenow"))
+            .or_else(|| {
+                cast::html_tag::<web_sys::HtmlInputElement>(element, "input")
+                    .map(|input| input.value())
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn is_inline(element: &Element) -> bool {
+    role::computed_style(element, None)
+        .and_then(|style| style.get_property_value("display").ok())
+        .map(|display| display.starts_with("inline") || display == "contents" || display.is_empty())
+        .unwrap_or(true)
+}
+
+/// The strings of a ::before or ::after content property, i.e `content: "★ "` gives "★ ".
+fn pseudo_content(element: &Element, pseudo: &str) -> String {
+    let Some(content) = role::computed_style(element, Some(pseudo))
+        .and_then(|style| style.get_property_value("content").ok())
+    else {
+        return String::new();
+    };
+    let mut text = String::new();
+    let mut quote = None;
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) if c == '\\' => text.extend(chars.next()),
+            Some(_) => text.push(c),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None => {}
+        }
+    }
+    text
+}
+
+fn non_empty_attribute(element: &Element, attr: &str) -> Option<String> {
+    element
+        .get_attribute(attr)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(all(test, feature = "unit_tests"))]
+pub mod test {
+    use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    pub fn name_from_labelledby_and_label() {
+        let mounted = Mounted::new(
+            "<div><span id='accname_first'>Billing</span><span id='accname_last'>Name</span>\
+            <input id='accname_input' aria-labelledby='accname_first accname_last'/>\
+            <label>Email <input id='accname_email'/></label></div>",
+        );
+        let input = mounted.query_selector("#accname_input").unwrap().unwrap();
+        assert_eq!(accessible_name(&input), "Billing Name");
+        let email = mounted.query_selector("#accname_email").unwrap().unwrap();
+        assert_eq!(accessible_name(&email), "Email");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn name_from_content_skips_hidden() {
+        let mounted = Mounted::new(
+            "<button>Save <span aria-hidden='true'>💾</span><span hidden>draft</span></button>",
+        );
+        let button = mounted.first();
+        assert_eq!(accessible_name(&button), "Save");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn description_from_describedby() {
+        let mounted = Mounted::new(
+            "<div><button aria-describedby='accname_hint' title='Remove'>X</button>\
+            <p id='accname_hint'>Deletes the row</p></div>",
+        );
+        let button = mounted.first().first_element_child().unwrap();
+        assert_eq!(accessible_name(&button), "X");
+        assert_eq!(accessible_description(&button), "Deletes the row");
+    }
+}
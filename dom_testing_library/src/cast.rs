@@ -0,0 +1,29 @@
+//! Casts that also work for nodes of a same-origin iframe.
+//! `dyn_into` checks `instanceof` against the constructors of the test's own window, which an iframe's nodes aren't instances of.
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, Node, SvgElement};
+
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// The node as an element, if it is one.
+pub(crate) fn element(node: &Node) -> Option<&Element> {
+    (node.node_type() == Node::ELEMENT_NODE).then(|| node.unchecked_ref())
+}
+
+/// The element as an html element, i.e not an svg element.
+pub(crate) fn html_element(element: &Element) -> Option<&HtmlElement> {
+    (element.namespace_uri().as_deref() == Some(HTML_NAMESPACE)).then(|| element.unchecked_ref())
+}
+
+/// The element as an svg element, i.e an <svg> or any of the shapes inside it.
+pub(crate) fn svg_element(element: &Element) -> Option<&SvgElement> {
+    (element.namespace_uri().as_deref() == Some(SVG_NAMESPACE)).then(|| element.unchecked_ref())
+}
+
+/// The element as the web_sys type of an html tag, i.e `html_tag::<HtmlInputElement>(element, "input")`.
+pub(crate) fn html_tag<'a, T: JsCast>(element: &'a Element, local_name: &str) -> Option<&'a T> {
+    (element.namespace_uri().as_deref() == Some(HTML_NAMESPACE)
+        && element.local_name() == local_name)
+        .then(|| element.unchecked_ref())
+}
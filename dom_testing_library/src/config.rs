@@ -0,0 +1,38 @@
+use crate::Normalizer;
+use std::cell::RefCell;
+
+/// Library wide configuration, shared by every query in the test thread.
+/// Change it with `configure`, i.e `configure(|config| config.test_id_attribute = "data-cy".into())`.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The attribute the get_by_test_id series matches against, defaults to `data-testid`.
+    pub test_id_attribute: String,
+    /// The normalizer text queries use unless their options bring their own, trims and collapses whitespace by default.
+    pub normalizer: Normalizer,
+    /// How long the find_by_X series and `wait_for` wait by default, in milliseconds.
+    pub async_util_timeout: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            test_id_attribute: String::from("data-testid"),
+            normalizer: Normalizer::default(),
+            async_util_timeout: 1000,
+        }
+    }
+}
+
+thread_local! {
+    static CONFIG: RefCell<Config> = RefCell::new(Config::default());
+}
+
+/// Update the library wide configuration.
+pub fn configure<F: FnOnce(&mut Config)>(f: F) {
+    CONFIG.with(|config| f(&mut config.borrow_mut()))
+}
+
+/// A snapshot of the current configuration.
+pub fn get_config() -> Config {
+    CONFIG.with(|config| config.borrow().clone())
+}
@@ -0,0 +1,348 @@
+use crate::{cast, DomQuery, GetOneError, TestElement};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+/// Elements that can have a name and take part in a form, https://html.spec.whatwg.org/#category-listed
+const FORM_CONTROL_TAGS: &[&str] = &[
+    "button", "fieldset", "input", "object", "output", "select", "textarea",
+];
+
+pub(crate) fn is_form_control(element: &Element) -> bool {
+    cast::html_element(element).is_some()
+        && FORM_CONTROL_TAGS.contains(&element.local_name().as_str())
+}
+
+/// The entries FormData builds from the form, in order, so the browser decides which controls are successful.
+fn form_entries(form: &Element) -> Vec<(String, String)> {
+    let Some(form) = cast::html_tag::<web_sys::HtmlFormElement>(form, "form") else {
+        return Vec::new();
+    };
+    let form_data =
+        web_sys::FormData::new_with_form(form).expect("FormData to be built from a form");
+    let Ok(Some(entries)) = js_sys::try_iter(&form_data) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let entry = entry.unchecked_into::<js_sys::Array>();
+            let name = entry.get(0).as_string().unwrap_or_default();
+            let value = entry.get(1);
+            // Files are listed by their name.
+            let value = value.as_string().unwrap_or_else(|| {
+                js_sys::Reflect::get(&value, &"name".into())
+                    .ok()
+                    .and_then(|name| name.as_string())
+                    .unwrap_or_default()
+            });
+            (name, value)
+        })
+        .collect()
+}
+
+pub(crate) fn form_values(form: &Element) -> BTreeMap<String, Vec<String>> {
+    let mut values: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in form_entries(form) {
+        values.entry(name).or_default().push(value);
+    }
+    values
+}
+
+/// The form values as a json object. Names that hold a list map to an array, even with no or one value,
+/// every other name maps to a string and is left out when it has no value, see `list_names`.
+pub(crate) fn form_values_json(form: &Element) -> Value {
+    let lists = list_names(form);
+    form_values(form)
+        .into_iter()
+        .map(|(name, mut values)| {
+            let value = if lists.contains(&name) || values.len() != 1 {
+                values.into()
+            } else {
+                Value::String(values.remove(0))
+            };
+            (name, value)
+        })
+        .chain(
+            lists
+                .iter()
+                .map(|name| (name.clone(), Value::Array(Vec::new()))),
+        )
+        .fold(serde_json::Map::new(), |mut json, (name, value)| {
+            json.entry(name).or_insert(value);
+            json
+        })
+        .into()
+}
+
+/// Names whose value is a list, from the markup rather than what's currently selected:
+/// a multiple select or file input, or several controls sharing the name other than a radio group, i.e a checkbox group.
+fn list_names(form: &Element) -> BTreeSet<String> {
+    let Some(form) = cast::html_tag::<web_sys::HtmlFormElement>(form, "form") else {
+        return BTreeSet::new();
+    };
+    let mut lists = BTreeSet::new();
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    let controls = form.elements();
+    for control in (0..controls.length()).filter_map(|i| controls.item(i)) {
+        let name = control.get_attribute("name").unwrap_or_default();
+        if name.is_empty() {
+            continue;
+        }
+        if let Some(input) = cast::html_tag::<web_sys::HtmlInputElement>(&control, "input") {
+            match input.type_().as_str() {
+                "radio" | "submit" | "reset" | "button" | "image" => continue,
+                "file" if input.multiple() => {
+                    lists.insert(name.clone());
+                }
+                _ => {}
+            }
+        } else if let Some(select) =
+            cast::html_tag::<web_sys::HtmlSelectElement>(&control, "select")
+        {
+            if select.multiple() {
+                lists.insert(name.clone());
+            }
+        } else if control.local_name() != "textarea" {
+            continue;
+        }
+        *counts.entry(name).or_default() += 1;
+    }
+    lists.extend(
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(name, _)| name),
+    );
+    lists
+}
+
+/// Fill each field of the form the way a user would, fields are found by name, or by label when no control has that name.
+/// Null values are skipped, arrays fill a checkbox group or a multiple select.
+pub(crate) fn fill_form(form: &TestElement, values: Value) -> Result<(), GetOneError> {
+    let Value::Object(fields) = values else {
+        return Err(GetOneError::cannot_fill(
+            &values.to_string(),
+            "fill_form takes a struct or map of field names to values",
+        ));
+    };
+    for (field, value) in fields {
+        if value.is_null() {
+            continue;
+        }
+        let mut controls = form.get_all_by_name(field.as_str());
+        if controls.is_empty() {
+            controls = form.get_all_by_label(field.as_str());
+        }
+        if controls.is_empty() {
+            return Err(GetOneError::cannot_fill(
+                &field,
+                "no control has this name or label",
+            ));
+        }
+        fill_controls(&field, &controls, &value)?;
+    }
+    Ok(())
+}
+
+/// Input types whose value is sanitized until it's complete, i.e "2" isn't a date, so they can't be typed a key at a time.
+const WHOLE_VALUE_TYPES: &[&str] = &[
+    "color",
+    "date",
+    "datetime-local",
+    "month",
+    "number",
+    "range",
+    "time",
+    "week",
+];
+
+fn fill_controls(field: &str, controls: &[TestElement], value: &Value) -> Result<(), GetOneError> {
+    let is_list = value.is_array();
+    let values = match value {
+        Value::Array(items) => items.iter().map(|item| scalar(field, item)).collect(),
+        value => scalar(field, value).map(|value| vec![value]),
+    }?;
+    let single_value = || {
+        if is_list {
+            Err(GetOneError::cannot_fill(
+                field,
+                "a list only fills a checkbox group or a multiple select",
+            ))
+        } else {
+            Ok(())
+        }
+    };
+    // Only the controls that change have to be enabled, i.e a disabled radio that isn't picked is fine.
+    let enabled = |control: &TestElement| {
+        if control.matches(":disabled").unwrap_or(false) {
+            Err(GetOneError::cannot_fill(field, "the control is disabled"))
+        } else {
+            Ok(())
+        }
+    };
+    let mut radios = false;
+    let mut radio_matched = false;
+    for control in controls {
+        if let Some(input) = cast::html_tag::<web_sys::HtmlInputElement>(control, "input") {
+            let input_type = input.type_();
+            match input_type.as_str() {
+                "checkbox" => {
+                    let checked = match value {
+                        Value::Bool(checked) => *checked,
+                        _ => values.contains(&input.value()),
+                    };
+                    if input.checked() != checked {
+                        enabled(control)?;
+                        input.click();
+                    }
+                }
+                "radio" => {
+                    single_value()?;
+                    radios = true;
+                    if values.contains(&input.value()) {
+                        radio_matched = true;
+                        if !input.checked() {
+                            enabled(control)?;
+                            input.click();
+                        }
+                    }
+                }
+                // Users can't type into these.
+                "hidden" | "file" | "submit" | "reset" | "button" | "image" => {}
+                _ => {
+                    single_value()?;
+                    enabled(control)?;
+                    if input.read_only() {
+                        return Err(GetOneError::cannot_fill(field, "the control is read only"));
+                    }
+                    type_text(
+                        input,
+                        values.first().map(String::as_str).unwrap_or_default(),
+                        !WHOLE_VALUE_TYPES.contains(&input_type.as_str()),
+                        |value| input.set_value(value),
+                    );
+                }
+            }
+        } else if let Some(textarea) =
+            cast::html_tag::<web_sys::HtmlTextAreaElement>(control, "textarea")
+        {
+            single_value()?;
+            enabled(control)?;
+            if textarea.read_only() {
+                return Err(GetOneError::cannot_fill(field, "the control is read only"));
+            }
+            type_text(
+                textarea,
+                values.first().map(String::as_str).unwrap_or_default(),
+                true,
+                |value| textarea.set_value(value),
+            );
+        } else if let Some(select) = cast::html_tag::<web_sys::HtmlSelectElement>(control, "select")
+        {
+            if !select.multiple() {
+                single_value()?;
+            }
+            enabled(control)?;
+            if let Some(missing) = select_options(select, &values) {
+                return Err(GetOneError::cannot_fill(
+                    field,
+                    format!("no option has the value or text {missing}"),
+                ));
+            }
+        }
+    }
+    if radios && !radio_matched {
+        return Err(GetOneError::cannot_fill(
+            field,
+            format!("no radio button has the value {}", values.join(", ")),
+        ));
+    }
+    Ok(())
+}
+
+/// The text a value fills a control with, objects and nested lists can't fill a control.
+fn scalar(field: &str, value: &Value) -> Result<String, GetOneError> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        value => Err(GetOneError::cannot_fill(
+            field,
+            format!("{value} isn't a string, number or boolean"),
+        )),
+    }
+}
+
+/// Focus the field, clear it and type the text a key at a time, then leave the field.
+/// Without `by_key` the whole text is set at once, for fields that reject a partial value.
+fn type_text<S: Fn(&str)>(element: &HtmlElement, text: &str, by_key: bool, set: S) {
+    let _ = element.focus();
+    set("");
+    dispatch(element, "input");
+    if by_key {
+        let mut typed = String::new();
+        for key in text.chars() {
+            let key = key.to_string();
+            dispatch_key(element, "keydown", &key);
+            typed.push_str(&key);
+            set(&typed);
+            dispatch(element, "input");
+            dispatch_key(element, "keyup", &key);
+        }
+    } else {
+        set(text);
+        dispatch(element, "input");
+    }
+    dispatch(element, "change");
+    let _ = element.blur();
+}
+
+/// Select the options whose value or text is one of the values, and deselect the rest.
+/// Returns the first value no option has, leaving the select as it was.
+fn select_options(select: &web_sys::HtmlSelectElement, values: &[String]) -> Option<String> {
+    let Ok(options) = select.query_selector_all("option") else {
+        return values.first().cloned();
+    };
+    let options = (0..options.length())
+        .filter_map(|i| options.item(i))
+        .map(|option| option.unchecked_into::<web_sys::HtmlOptionElement>())
+        .collect::<Vec<_>>();
+    let picks = |option: &web_sys::HtmlOptionElement, value: &String| {
+        &option.value() == value || option.text_content().unwrap_or_default().trim() == value
+    };
+    if let Some(missing) = values
+        .iter()
+        .find(|value| !options.iter().any(|option| picks(option, value)))
+    {
+        return Some(missing.clone());
+    }
+    let _ = select.focus();
+    for option in options {
+        let selected = values.iter().any(|value| picks(&option, value));
+        if selected || select.multiple() {
+            option.set_selected(selected);
+        }
+    }
+    dispatch(select, "input");
+    dispatch(select, "change");
+    let _ = select.blur();
+    None
+}
+
+fn dispatch(element: &HtmlElement, event_type: &str) {
+    let mut init = web_sys::EventInit::new();
+    init.bubbles(true);
+    let event = web_sys::Event::new_with_event_init_dict(event_type, &init)
+        .expect("an event to be constructed");
+    let _ = element.dispatch_event(&event);
+}
+
+fn dispatch_key(element: &HtmlElement, event_type: &str, key: &str) {
+    let mut init = web_sys::KeyboardEventInit::new();
+    init.bubbles(true).cancelable(true).key(key);
+    let event = web_sys::KeyboardEvent::new_with_keyboard_event_init_dict(event_type, &init)
+        .expect("a keyboard event to be constructed");
+    let _ = element.dispatch_event(&event);
+}
@@ -0,0 +1,145 @@
+use crate::cast;
+use crate::query_root::{element_by_id, tree_query_selector_all};
+use crate::{GetOneError, TestElement};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
+
+/// Elements that can be associated with a <label>, https://html.spec.whatwg.org/#category-label
+const LABELABLE_TAGS: &[&str] = &[
+    "BUTTON", "INPUT", "METER", "OUTPUT", "PROGRESS", "SELECT", "TEXTAREA",
+];
+
+/// Every text the element is labelled by, a screen reader would announce any of them as its label.
+/// That's each associated <label>, each element referenced by aria-labelledby along with all of them joined,
+/// and aria-label.
+pub(crate) fn label_texts(element: &Element) -> Vec<String> {
+    let mut texts = Vec::new();
+    if LABELABLE_TAGS.contains(&element.tag_name().to_uppercase().as_str()) {
+        texts.extend(labels_of(element).iter().map(label_text));
+    }
+    if let Some(ids) = element.get_attribute("aria-labelledby") {
+        let labelled_by = labelled_by(element, &ids);
+        if labelled_by.len() > 1 {
+            texts.push(
+                labelled_by
+                    .iter()
+                    .map(label_text)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        texts.extend(labelled_by.iter().map(label_text));
+    }
+    if let Some(aria_label) = element.get_attribute("aria-label") {
+        texts.push(aria_label);
+    }
+    texts
+}
+
+/// Label elements in the element's document or shadow root whose labeled control is this element,
+/// whether by for= or by wrapping it.
+pub(crate) fn labels_of(element: &Element) -> Vec<Element> {
+    tree_query_selector_all(element, "label")
+        .into_iter()
+        .map(|label| label.unchecked_into::<web_sys::HtmlLabelElement>())
+        .filter(|label| {
+            label
+                .control()
+                .map(|control| control.is_same_node(Some(element)))
+                .unwrap_or(false)
+        })
+        .map(Into::into)
+        .collect()
+}
+
+/// Why none of the elements whose text matched a label query got associated with a control, if one of them is a <label>.
+/// Used to turn a plain NotFound into an error that points at the broken label.
+pub(crate) fn unassociated_label_error(text_matches: Vec<TestElement>) -> Option<GetOneError> {
+    text_matches.iter().find_map(|element| {
+        let label = element.closest("label").ok()??;
+        let label = cast::html_tag::<web_sys::HtmlLabelElement>(&label, "label")?;
+        if label.control().is_some() {
+            return None;
+        }
+        let for_id = label.get_attribute("for");
+        let labelled = for_id.as_ref().and_then(|id| element_by_id(label, id));
+        Some(match labelled {
+            Some(labelled) => GetOneError::LabelledElementNotLabelable {
+                label: label.outer_html(),
+                element: labelled.outer_html(),
+            },
+            None => GetOneError::LabelWithoutControl {
+                label: label.outer_html(),
+                for_id,
+            },
+        })
+    })
+}
+
+/// The elements an aria-labelledby id list points to, missing ids are skipped.
+fn labelled_by(element: &Element, ids: &str) -> Vec<Element> {
+    ids.split_whitespace()
+        .filter_map(|id| element_by_id(element, id))
+        .collect()
+}
+
+/// The text of a label, leaving out the content of any control inside it,
+/// i.e `<label>Country <select><option>Norway</option></select></label>` is labelled "Country ".
+pub(crate) fn label_text(label: &Element) -> String {
+    let mut text = String::new();
+    push_label_text(label.as_ref(), &mut text);
+    text
+}
+
+fn push_label_text(node: &Node, text: &mut String) {
+    let children = node.child_nodes();
+    for i in 0..children.length() {
+        let Some(child) = children.item(i) else {
+            continue;
+        };
+        match child.node_type() {
+            Node::TEXT_NODE => text.push_str(&child.text_content().unwrap_or_default()),
+            Node::ELEMENT_NODE => {
+                let tag = child.unchecked_ref::<Element>().tag_name().to_uppercase();
+                if !LABELABLE_TAGS.contains(&tag.as_str()) {
+                    push_label_text(&child, text);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(all(test, feature = "unit_tests"))]
+pub mod test {
+    use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    pub fn wrapping_and_multiple_labels() {
+        let mounted = Mounted::new(
+            "<label>Country <select id='label_country'><option>Norway</option></select></label>\
+            <label for='label_country'>Shipping</label>",
+        );
+        let select = mounted.query_selector("#label_country").unwrap().unwrap();
+        let texts = label_texts(&select);
+        assert_eq!(
+            texts,
+            vec![String::from("Country "), String::from("Shipping")]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn aria_labelledby_and_aria_label() {
+        let mounted = Mounted::new(
+            "<span id='label_billing'>Billing</span><span id='label_name'>Name</span>\
+            <input id='label_input' aria-labelledby='label_billing label_name' aria-label='Full name'/>",
+        );
+        let input = mounted.query_selector("#label_input").unwrap().unwrap();
+        let texts = label_texts(&input);
+        assert!(texts.contains(&String::from("Billing Name")));
+        assert!(texts.contains(&String::from("Name")));
+        assert!(texts.contains(&String::from("Full name")));
+    }
+}
@@ -1,106 +1,466 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::ops::Deref; //
 use thiserror::Error;
-use wasm_bindgen::JsCast;
-use web_sys::{Document, HtmlElement, Node};
+use web_sys::{Document, Element, HtmlElement, Node};
+
+mod accname;
+mod cast;
+mod config;
+mod form;
+mod label;
+mod outline;
+mod query_options;
+mod query_root;
+mod role;
+mod table;
+#[cfg(all(test, feature = "unit_tests"))]
+mod test_utils;
+mod text_match;
+mod wait_for;
+pub use config::{configure, get_config, Config};
+pub use outline::{Heading, Outline};
+pub use query_options::QueryOptions;
+use query_root::{QueryRoot, Scope};
+pub use regex::Regex;
+pub use role::RoleOptions;
+pub use text_match::{MatcherFn, Normalizer, TextMatch};
+pub use wait_for::{wait_for, WaitForOptions};
+/// Generates a DomQuery family around its get_all_by_X_with, i.e `by_text` gives get_by_text, get_all_by_text,
+/// query_by_text, query_all_by_text, find_by_text, find_all_by_text and their `_with` versions.
+/// The snippet says which elements match and starts the docs, the doc comments are added to get_by_X.
+/// A `=> conversion` on an argument is applied once before the input is used, i.e to turn it into a `TextMatch`.
+/// `ident` is how errors show the input, the argument by default.
+/// `elements` replaces the get_all_by_X_with call where getting one element can fail with more than NotFound.
+/// get_all_by_X_with is left to implementors unless the family gives it a body with `get_all_with`.
+macro_rules! query_family {
+    (@get_all_with [$($sig:tt)*] []) => {
+        $($sig)*;
+    };
+    (@get_all_with [$($sig:tt)*] [$body:block]) => {
+        $($sig)* $body
+    };
+    (@ident [] $arg:ident) => {
+        &$arg
+    };
+    (@ident [$ident:expr] $($arg:ident)+) => {
+        $ident
+    };
+    (@elements [] $this:ident.$get_all_with:ident($($arg:ident)+, $options:ident)) => {
+        ElementList::into_result($this.$get_all_with($($arg.clone()),+, $options))
+    };
+    (@elements [$elements:expr] $($rest:tt)*) => {
+        $elements
+    };
+    (
+        $(#[$doc:meta])*
+        $what:literal
+        fn $family:ident$(<$gen:ident: $bound:path>)?(
+            &$this:ident,
+            $($arg:ident: $ty:ty $(=> $conv:expr)?),+;
+            $options:ident: $options_ty:ident
+        ) -> $list:ty;
+        $(ident = $ident:expr;)?
+        $(elements = $elements:expr;)?
+        $(get_all_with = $body:block)?
+    ) => {
+        paste::paste! {
+            #[doc = concat!("Get the element ", $what, ".")]
+            $(#[$doc])*
+            fn [<get_ $family>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty),+
+            ) -> Result<TestElement, GetOneError> {
+                $this.[<get_ $family _with>]($($arg),+, $options_ty::default())
+            }
+            #[doc = concat!("Get all elements ", $what, ", see get_", stringify!($family), ".")]
+            fn [<get_all_ $family>]$(<$gen: $bound>)?(&$this, $($arg: $ty),+) -> $list {
+                $this.[<get_all_ $family _with>]($($arg),+, $options_ty::default())
+            }
+            #[doc = concat!("See get_", stringify!($family), ", with `", stringify!($options_ty), "`.")]
+            fn [<get_ $family _with>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty,)+
+                $options: $options_ty,
+            ) -> Result<TestElement, GetOneError> {
+                $($(let $arg = $conv;)?)+
+                let ident = query_family!(@ident [$($ident)?] $($arg)+).to_string();
+                get_one_inner(
+                    query_family!(@elements [$($elements)?]
+                        $this.[<get_all_ $family _with>]($($arg)+, $options))?,
+                    stringify!($family),
+                    ident,
+                )
+            }
+            query_family!(@get_all_with [
+                #[doc = concat!("See get_all_", stringify!($family), ", with `", stringify!($options_ty), "`.")]
+                fn [<get_all_ $family _with>]$(<$gen: $bound>)?(
+                    &$this,
+                    $($arg: $ty,)+
+                    $options: $options_ty,
+                ) -> $list
+            ] [$($body)?]);
+            #[doc = concat!(
+                "Like get_",
+                stringify!($family),
+                ", but returns Ok(None) when nothing matches so you can assert an element is absent."
+            )]
+            fn [<query_ $family>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty),+
+            ) -> Result<Option<TestElement>, GetOneError> {
+                $this.[<query_ $family _with>]($($arg),+, $options_ty::default())
+            }
+            #[doc = concat!("Like get_", stringify!($family), "_with, but returns Ok(None) when nothing matches.")]
+            fn [<query_ $family _with>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty,)+
+                $options: $options_ty,
+            ) -> Result<Option<TestElement>, GetOneError> {
+                $($(let $arg = $conv;)?)+
+                let ident = query_family!(@ident [$($ident)?] $($arg)+).to_string();
+                query_one_inner(
+                    query_family!(@elements [$($elements)?]
+                        $this.[<get_all_ $family _with>]($($arg)+, $options))?,
+                    stringify!($family),
+                    ident,
+                )
+            }
+            #[doc = concat!("The same as get_all_", stringify!($family), ", an empty list when nothing matches.")]
+            fn [<query_all_ $family>]$(<$gen: $bound>)?(&$this, $($arg: $ty),+) -> $list {
+                $this.[<get_all_ $family>]($($arg),+)
+            }
+            #[doc = concat!("The same as get_all_", stringify!($family), "_with, an empty list when nothing matches.")]
+            fn [<query_all_ $family _with>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty,)+
+                $options: $options_ty,
+            ) -> $list {
+                $this.[<get_all_ $family _with>]($($arg),+, $options)
+            }
+            #[doc = concat!("Wait for exactly one element to appear, see get_", stringify!($family), ".")]
+            /// Fails with `GetOneError::Timeout` holding the last query error if it doesn't within the configured timeout.
+            fn [<find_ $family>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty),+
+            ) -> impl Future<Output = Result<TestElement, GetOneError>> {
+                $this.[<find_ $family _with>](
+                    $($arg,)+
+                    $options_ty::default(),
+                    WaitForOptions::default(),
+                )
+            }
+            #[doc = concat!("Wait for exactly one element to appear, see get_", stringify!($family), "_with.")]
+            fn [<find_ $family _with>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty,)+
+                $options: $options_ty,
+                wait_for_options: WaitForOptions,
+            ) -> impl Future<Output = Result<TestElement, GetOneError>> {
+                $($(let $arg = $conv;)?)+
+                wait_for(
+                    move || $this.[<get_ $family _with>]($($arg.clone()),+, $options.clone()),
+                    wait_for_options,
+                )
+            }
+            #[doc = concat!("Wait for at least one element to appear, see get_all_", stringify!($family), ".")]
+            fn [<find_all_ $family>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty),+
+            ) -> impl Future<Output = Result<Vec<TestElement>, GetOneError>> {
+                $this.[<find_all_ $family _with>](
+                    $($arg,)+
+                    $options_ty::default(),
+                    WaitForOptions::default(),
+                )
+            }
+            #[doc = concat!("Wait for at least one element to appear, see get_all_", stringify!($family), "_with.")]
+            fn [<find_all_ $family _with>]$(<$gen: $bound>)?(
+                &$this,
+                $($arg: $ty,)+
+                $options: $options_ty,
+                wait_for_options: WaitForOptions,
+            ) -> impl Future<Output = Result<Vec<TestElement>, GetOneError>> {
+                $($(let $arg = $conv;)?)+
+                wait_for(
+                    move || {
+                        let $options = $options.clone();
+                        let ident = query_family!(@ident [$($ident)?] $($arg)+).to_string();
+                        find_all_inner(
+                            query_family!(@elements [$($elements)?]
+                                $this.[<get_all_ $family _with>]($($arg)+, $options))?,
+                            stringify!($family),
+                            ident,
+                        )
+                    },
+                    wait_for_options,
+                )
+            }
+        }
+    };
+}
+
 /// DomQuery is a trait that is implement on a data structure that holds onto the document for the test.
 /// The get_by_X series tries to get exactly one element given the input by method of X
 /// They return an `Error::NotFound` result if 0, or a `MoreThanOne` error if more than one.
 /// The get_all_by_X series returns a list of 0 or more items given the input by method of ˙˙˙
+/// The query_by_X series is like get_by_X but returns `Ok(None)` if 0, for asserting something isn't rendered.
+/// The query_all_by_X series is the same as get_all_by_X, it's there so the query_by_X family reads consistently.
+/// The find_by_X and find_all_by_X series are async, they wait for the get_by_X or a non empty get_all_by_X to succeed,
+/// i.e `render.find_by_text("Loaded").await`, see `wait_for`.
+/// Text based inputs take anything that converts into a `TextMatch`, strings match exactly,
+/// i.e `get_by_text(TextMatch::contains("Items"))` or `get_by_text(Regex::new(r"Items \(\d+\)").unwrap())`.
+/// Every query has a `_with` version taking options, the text of elements is normalized before matching,
+/// see `QueryOptions` and `Config::normalizer`.
+/// Elements users can't perceive, i.e display:none or aria-hidden="true", are left out unless `QueryOptions::hidden` is set.
+/// Queries stop at shadow roots and iframes unless `QueryOptions::pierce_shadow_dom` or `QueryOptions::pierce_iframes` is set.
+/// Implementors only provide the get_all_by_X_with methods, the rest are built on top of them.
 pub trait DomQuery {
-    /// Get the element whose inner text matches this method's input, exactly.
-    /// See get_by_text_contains for a non-exact matching method.
-    fn get_by_text<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError>;
-    /// Get all elements whose inner text matches this method's input, exactly.
-    /// Seeget_by_text_contains for a non-exact matching method.
-    fn get_all_by_text<S: AsRef<str>>(&self, text: S) -> Vec<TestElement>;
-    /// Get an element whose inner text contains the text content, i.e "abc" contains "a".
-    /// Seeget_by_text for an exact matcher.
-    fn get_by_text_contains<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError>;
-    /// Get a list of elements whose inner text contains the text content, i.e "abc" contains "a".
-    /// Seeget_by_text for an exact matcher.
-    fn get_all_by_text_contains<S: AsRef<str>>(&self, text: S) -> Vec<TestElement>;
-    /// Get an element by it's id, matches exactly. See get_by_id_contains for non-exact matching.
-    fn get_by_id<S: AsRef<str>>(&self, id: S) -> Result<TestElement, GetOneError>;
-    /// Get all elements by their id, matches exactly.
-    fn get_all_by_id<S: AsRef<str>>(&self, id: S) -> Vec<TestElement>;
-    /// Get an element whose id contains the text string, see get_by_id for exact id matching.
-    fn get_by_id_contains<S: AsRef<str>>(&self, id: S) -> Result<TestElement, GetOneError>;
-    /// Get a list of elements whose id contains the text string, see get_by_id for exact id matching.
-    fn get_all_by_id_contains<S: AsRef<str>>(&self, id: S) -> Vec<TestElement>;
-    /// Get the element that is pointed to by a label whose text is the input of the method.
-    /// i.e <label for="field">Btn</label><input id="field"/>
-    /// With input of "field" would return the input whose id is field.
-    /// If you want to find the label element itself, see get_by_text
-    fn get_by_label<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError>;
-    /// Get a list of  elements that are pointed to by a label whose text is the input of the method.
-    /// i.e <label for="field">Btn</label><input id="field"/>
-    /// With input of "field" would return the input whose id is field.
-    /// If you want to find the label element itself, see get_by_text
-    fn get_all_by_label<S: AsRef<str>>(&self, text: S) -> Vec<TestElement>;
-    /// Get the element that is pointed to by a label whose text is the input of the method.
-    /// i.e <label for="field">Btn</label><input id="field"/>
-    /// With input of "field" would return the input whose id is field.
-    /// If you want to find the label element itself, see get_by_text
-    fn get_by_label_contains<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError>;
-    /// Get a list of  elements that are pointed to by a label whose text is the input of the method.
-    /// i.e <label for="field">Btn</label><input id="field"/>
-    /// With input of "field" would return the input whose id is field.
-    /// If you want to find the label element itself, see get_by_text
-    fn get_all_by_label_contains<S: AsRef<str>>(&self, text: S) -> Vec<TestElement>;
-    /// Get elements whose display value is the exact match of this methods input.
-    /// The elements that this method will find are: input, textarea, and select.
-    /// This method will not match against items with non-display value attributes, i.e option, progress, li etc.
-    fn get_by_display_value<S: AsRef<str>>(&self, value: S) -> Result<TestElement, GetOneError>;
-    /// Get elements whose display value is the exact match of this methods input.
-    /// The elements that this method will find are: input, textarea, and select.
-    /// This method will not match against items with non-display value attributes, i.e option, progress, li etc.
-    fn get_all_by_display_value<S: AsRef<str>>(&self, value: S) -> Vec<TestElement>;
-    /// Get an element matching ARIA role.
-    fn get_by_role<S: AsRef<str>>(&self, role: S) -> Result<TestElement, GetOneError>;
-    /// Get a list of elements matching AIRA role.
-    fn get_all_by_role<S: AsRef<str>>(&self, role: S) -> Vec<TestElement>;
-    /// Get by placeholder text, checks textarea and input only. As those are the only applicable elements with placeholders.
-    /// Matches exactly.
-    fn get_by_placeholder_text<S: AsRef<str>>(
-        &self,
-        placeholder_text: S,
-    ) -> Result<TestElement, GetOneError>;
-    /// Get a list of elements by placeholder text, checks textarea and input only. As those are the only applicable elements with placeholders.
-    /// Matches exactly.
-    fn get_all_by_placeholder_text<S: AsRef<str>>(&self, placeholder_text: S) -> Vec<TestElement>;
-    /// Get by placeholder text, checks textarea and input only. As those are the only applicable elements with placeholders.
-    /// Checks if placeholder text contains method input.
-    fn get_by_placeholder_text_contains<S: AsRef<str>>(
-        &self,
-        placeholder_text: S,
-    ) -> Result<TestElement, GetOneError>;
-    /// Get a list of elements by placeholder text, checks textarea and input only. As those are the only applicable elements with placeholders.
-    /// Checks if placeholder text contains method input.
-    fn get_all_by_placeholder_text_contains<S: AsRef<str>>(
-        &self,
-        placeholder_text: S,
-    ) -> Vec<TestElement>;
+    query_family! {
+        "whose inner text matches the input"
+        fn by_text<M: Into<TextMatch>>(
+            &self,
+            text: M => text.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        "whose id matches the input"
+        fn by_id<M: Into<TextMatch>>(
+            &self,
+            id: M => id.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        /// The attribute is `data-testid` by default, see `configure` to use another i.e `data-cy`.
+        "whose test id attribute matches the input"
+        fn by_test_id<M: Into<TextMatch>>(
+            &self,
+            test_id: M => test_id.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        /// i.e <label for="field">Btn</label><input id="field"/>
+        /// With input of "Btn" would return the input whose id is field.
+        /// Labels are associated the way screen readers associate them: a <label for>, a <label> wrapping the control,
+        /// aria-labelledby (matching each referenced element or all of them joined) and aria-label.
+        /// If you want to find the label element itself, see get_by_text
+        /// When nothing is labelled by the text but a <label> with that text exists, the error says what's wrong with the label,
+        /// i.e `LabelWithoutControl` for a for= pointing at a missing id, query_by_label and find_by_label fail the same way.
+        "labelled by the input"
+        fn by_label<M: Into<TextMatch>>(
+            &self,
+            text: M => text.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+        elements = labelled_elements(self, &text, options);
+    }
+    query_family! {
+        /// The elements that this method will find are: input, textarea, and select.
+        /// A select matches on the text of its selected option, any selected option of a multiple select can match.
+        /// This method will not match against items with non-display value attributes, i.e option, progress, li etc.
+        /// or checkboxes and radios, find those by label or role instead.
+        "whose display value matches the input"
+        fn by_display_value<M: Into<TextMatch>>(
+            &self,
+            value: M => value.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        /// Elements match on their explicit role attribute, or on the implicit role the HTML-AAM gives them,
+        /// i.e a plain <button> has the role "button" and an <a href="/"> has the role "link".
+        /// The options narrow it down by the accessible name, states and properties,
+        /// i.e with <button>Cancel</button><button>Save</button> a role of "button" and a name of "Save" returns the second button.
+        /// Elements excluded from the accessibility tree are ignored unless `query.hidden` is set.
+        "matching the ARIA role"
+        fn by_role<S: AsRef<str>>(
+            &self,
+            role: S => role.as_ref().to_string();
+            options: RoleOptions
+        ) -> Vec<TestElement>;
+        ident = format!("{role} with {options:?}");
+    }
+    query_family! {
+        /// i.e `get_by_heading(2, "Shoes")` finds <h2>Shoes</h2>.
+        /// Headings are h1-h6 and elements with role="heading", whose level comes from aria-level.
+        /// The name and level of the role options are always set from the input.
+        "with the heading role whose level and accessible name match the input"
+        fn by_heading<M: Into<TextMatch>>(
+            &self,
+            level: u32,
+            text: M => text.into();
+            options: RoleOptions
+        ) -> Vec<TestElement>;
+        ident = format!("level {level} {text}");
+        get_all_with = {
+            self.get_all_by_role_with(
+                "heading",
+                RoleOptions {
+                    name: Some(text.into()),
+                    level: Some(level),
+                    ..options
+                },
+            )
+        }
+    }
+    query_family! {
+        /// i.e `get_by_link("Settings")` finds <a href="/settings">Settings</a>.
+        /// Links are elements with the link role, an <a> or <area> needs an href to be one. See `TestElement::href_resolved`.
+        /// The name of the role options is always set from the input.
+        "with the link role whose accessible name matches the input"
+        fn by_link<M: Into<TextMatch>>(
+            &self,
+            text: M => text.into();
+            options: RoleOptions
+        ) -> Vec<TestElement>;
+        get_all_with = {
+            self.get_all_by_role_with(
+                "link",
+                RoleOptions {
+                    name: Some(text.into()),
+                    ..options
+                },
+            )
+        }
+    }
+    query_family! {
+        /// The elements that this method will find are: img, area, input type="image" and custom elements with an alt attribute.
+        "whose alt text matches the input"
+        fn by_alt_text<M: Into<TextMatch>>(
+            &self,
+            alt_text: M => alt_text.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        /// Matches the title attribute of any element and the <title> child of an svg element.
+        /// For svg titles the svg element itself is returned, use parent_test_element to reach i.e the icon button.
+        "whose title matches the input"
+        fn by_title<M: Into<TextMatch>>(
+            &self,
+            title: M => title.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        /// Checks textarea and input only, as those are the only applicable elements with placeholders.
+        "whose placeholder text matches the input"
+        fn by_placeholder_text<M: Into<TextMatch>>(
+            &self,
+            placeholder_text: M => placeholder_text.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        /// i.e <input name="email"/> with an input of "email", or every radio of a group with get_all_by_name.
+        /// The elements that this method will find are: button, fieldset, input, object, output, select and textarea.
+        "whose name attribute matches the input"
+        fn by_name<M: Into<TextMatch>>(
+            &self,
+            name: M => name.into();
+            options: QueryOptions
+        ) -> Vec<TestElement>;
+    }
+    query_family! {
+        /// An escape hatch for structural queries the other queries can't express,
+        /// i.e `get_by_selector("tbody > tr:first-child")`. Prefer queries that reflect what the user sees where you can.
+        /// Fails with `InvalidSelector` if the selector doesn't parse, so do get_all_by_selector and the rest of the family.
+        "matching a css selector"
+        fn by_selector(
+            &self,
+            selector: &str;
+            options: QueryOptions
+        ) -> Result<Vec<TestElement>, GetOneError>;
+    }
 }
 
 #[derive(Error, Debug, PartialEq)]
 pub enum GetOneError {
     #[error("Not Found:Attempting to find: {ident} by method {method}")]
     NotFound { method: &'static str, ident: String },
-    #[error("Found more than one element by method of get_{method} with input of {ident}, if you were expecting more than one match see the get_all_{method} version of this method instead.")]
+    #[error(
+        "Found more than one element by {} with input of {ident}, {}",
+        method_name(method),
+        more_than_one_hint(method)
+    )]
     MoreThanOne { method: &'static str, ident: String },
+    #[error("Timed out after {timeout}ms waiting, the last attempt failed with: {last_error}")]
+    Timeout {
+        timeout: u32,
+        last_error: Box<GetOneError>,
+    },
+    #[error("{selector} is not a valid css selector")]
+    InvalidSelector { selector: String },
+    #[error("Found the label {label} but no form control is associated with it, make sure its for attribute is the id of a control, it wraps the control, or the control uses aria-labelledby.")]
+    LabelWithoutControl {
+        label: String,
+        for_id: Option<String>,
+    },
+    #[error("Found the label {label} but the element it's for, {element}, can't be labelled by a <label>, use a labelable element like input, select or textarea, or use aria-labelledby.")]
+    LabelledElementNotLabelable { label: String, element: String },
+    #[error("Can't fill {field}: {reason}")]
+    CannotFill { field: String, reason: String },
 }
 
 impl GetOneError {
     fn more_than_one(method: &'static str, ident: String) -> Self {
         Self::MoreThanOne { method, ident }
     }
-    fn not_found(method: &'static str, ident: String) -> Self {
+    pub(crate) fn not_found(method: &'static str, ident: String) -> Self {
         Self::NotFound { method, ident }
     }
+    fn invalid_selector(selector: &str) -> Self {
+        Self::InvalidSelector {
+            selector: selector.to_string(),
+        }
+    }
+    pub(crate) fn cannot_fill<S: ToString>(field: &str, reason: S) -> Self {
+        Self::CannotFill {
+            field: field.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+    fn timeout(timeout: u32, last_error: GetOneError) -> Self {
+        Self::Timeout {
+            timeout,
+            last_error: Box::new(last_error),
+        }
+    }
+}
+/// The method an error came from, the query methods are named get_by_X, the table helpers by their own name.
+fn method_name(method: &str) -> String {
+    if method.starts_with("by_") {
+        format!("get_{method}")
+    } else {
+        method.to_string()
+    }
+}
+
+/// What to do about more than one match, pointing at a method that exists.
+fn more_than_one_hint(method: &str) -> String {
+    match method {
+        "row_by_text" => String::from("if you were expecting more than one row see rows_by_text instead."),
+        "cell" => String::from(
+            "the row or column header matches several rows or columns, match it more narrowly i.e with TextMatch::exact.",
+        ),
+        method => format!(
+            "if you were expecting more than one match see the get_all_{method} version of this method instead."
+        ),
+    }
 }
+
 pub trait GetOneErrorTrait {
     fn is_not_found(&self) -> bool;
     fn is_more_than_one(&self) -> bool;
+    fn is_timeout(&self) -> bool;
+    fn is_invalid_selector(&self) -> bool;
+    fn is_label_without_control(&self) -> bool;
+    fn is_labelled_element_not_labelable(&self) -> bool;
+    fn is_cannot_fill(&self) -> bool;
 }
 
 impl GetOneErrorTrait for GetOneError {
@@ -110,6 +470,21 @@ impl GetOneErrorTrait for GetOneError {
     fn is_more_than_one(&self) -> bool {
         matches!(self, GetOneError::MoreThanOne { .. })
     }
+    fn is_timeout(&self) -> bool {
+        matches!(self, GetOneError::Timeout { .. })
+    }
+    fn is_invalid_selector(&self) -> bool {
+        matches!(self, GetOneError::InvalidSelector { .. })
+    }
+    fn is_label_without_control(&self) -> bool {
+        matches!(self, GetOneError::LabelWithoutControl { .. })
+    }
+    fn is_labelled_element_not_labelable(&self) -> bool {
+        matches!(self, GetOneError::LabelledElementNotLabelable { .. })
+    }
+    fn is_cannot_fill(&self) -> bool {
+        matches!(self, GetOneError::CannotFill { .. })
+    }
 }
 
 impl<T> GetOneErrorTrait for Result<T, GetOneError> {
@@ -125,36 +500,285 @@ impl<T> GetOneErrorTrait for Result<T, GetOneError> {
             Err(err) => matches!(err, GetOneError::MoreThanOne { .. }),
         }
     }
+    fn is_timeout(&self) -> bool {
+        match &self {
+            Ok(_) => false,
+            Err(err) => matches!(err, GetOneError::Timeout { .. }),
+        }
+    }
+    fn is_invalid_selector(&self) -> bool {
+        match &self {
+            Ok(_) => false,
+            Err(err) => matches!(err, GetOneError::InvalidSelector { .. }),
+        }
+    }
+    fn is_label_without_control(&self) -> bool {
+        match &self {
+            Ok(_) => false,
+            Err(err) => matches!(err, GetOneError::LabelWithoutControl { .. }),
+        }
+    }
+    fn is_labelled_element_not_labelable(&self) -> bool {
+        match &self {
+            Ok(_) => false,
+            Err(err) => matches!(err, GetOneError::LabelledElementNotLabelable { .. }),
+        }
+    }
+    fn is_cannot_fill(&self) -> bool {
+        match &self {
+            Ok(_) => false,
+            Err(err) => matches!(err, GetOneError::CannotFill { .. }),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct TestElement(pub HtmlElement);
+pub struct TestElement(pub Element);
 
 impl TestElement {
-    /// Click outside of an element. This will try to turn the parent node of the element into an HtmlElement and click on it. If the parent
-    /// node isn't an HtmlElement this will panic.
+    /// The element as an html element, None for svg and other non html elements.
+    pub fn as_html(&self) -> Option<&HtmlElement> {
+        cast::html_element(&self.0)
+    }
+
+    /// The element as an svg element, i.e an icon or a chart drawn with svg.
+    pub fn as_svg(&self) -> Option<&web_sys::SvgElement> {
+        cast::svg_element(&self.0)
+    }
+
+    /// Click the element. Html elements are clicked natively, other elements get a bubbling click event dispatched to them.
+    pub fn click(&self) {
+        match self.as_html() {
+            Some(element) => element.click(),
+            None => {
+                let mut init = web_sys::MouseEventInit::new();
+                init.bubbles(true).cancelable(true);
+                let event = web_sys::MouseEvent::new_with_mouse_event_init_dict("click", &init)
+                    .expect("a click event to be constructed");
+                let _ = self.0.dispatch_event(&event);
+            }
+        }
+    }
+
+    /// The rendered text of the element, the text content for elements that aren't html elements.
+    pub fn inner_text(&self) -> String {
+        match self.as_html() {
+            Some(element) => element.inner_text(),
+            None => self.0.text_content().unwrap_or_default(),
+        }
+    }
+
+    /// Click outside of an element, this clicks the parent of the element.
+    /// Panics if the element has no parent.
     pub fn click_outside(&self) {
-        self.0
-            .parent_element()
+        self.parent_test_element()
             .expect("click_outside to be called on an element with a parent.")
-            .unchecked_into::<HtmlElement>()
             .click()
     }
 
+    /// The parent element, None for the root of the document or of a detached tree.
+    pub fn parent_test_element(&self) -> Option<TestElement> {
+        self.0.parent_element().map(TestElement)
+    }
+
+    /// The element itself or its nearest ancestor with the role, i.e the card containing a title with
+    /// `title.closest_by_role("article")`.
+    pub fn closest_by_role<S: AsRef<str>>(&self, role: S) -> Option<TestElement> {
+        let mut current = Some(self.clone());
+        while let Some(element) = current {
+            if element.role().as_deref() == Some(role.as_ref()) {
+                return Some(element);
+            }
+            current = element.parent_test_element();
+        }
+        None
+    }
+
+    /// The other children of the element's parent, in document order.
+    pub fn siblings(&self) -> Vec<TestElement> {
+        self.parent_test_element()
+            .map(|parent| {
+                parent
+                    .children_matching(|_| true)
+                    .into_iter()
+                    .filter(|sibling| sibling != self)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The first following sibling with the role, i.e the panel after a tab.
+    pub fn next_by_role<S: AsRef<str>>(&self, role: S) -> Option<TestElement> {
+        let mut current = self.0.next_element_sibling();
+        while let Some(element) = current {
+            let element = TestElement(element);
+            if element.role().as_deref() == Some(role.as_ref()) {
+                return Some(element);
+            }
+            current = element.0.next_element_sibling();
+        }
+        None
+    }
+
+    /// The nearest preceding sibling with the role.
+    pub fn previous_by_role<S: AsRef<str>>(&self, role: S) -> Option<TestElement> {
+        let mut current = self.0.previous_element_sibling();
+        while let Some(element) = current {
+            let element = TestElement(element);
+            if element.role().as_deref() == Some(role.as_ref()) {
+                return Some(element);
+            }
+            current = element.0.previous_element_sibling();
+        }
+        None
+    }
+
+    /// The direct children of the element the query accepts, in document order,
+    /// i.e `list.children_matching(|item| item.get_by_text("Done").is_ok())`.
+    pub fn children_matching<F: Fn(&TestElement) -> bool>(&self, query: F) -> Vec<TestElement> {
+        let children = self.0.children();
+        (0..children.length())
+            .filter_map(|i| children.item(i))
+            .map(TestElement)
+            .filter(|child| query(child))
+            .collect()
+    }
+
+    /// The ARIA role of the element, explicit roles take precedence over the implicit role of the tag.
+    /// Returns None for elements that have no corresponding role.
+    pub fn role(&self) -> Option<String> {
+        role::element_role(&self.0)
+    }
+
+    /// The accessible name of the element, what a screen reader announces it as.
+    /// Computed per https://www.w3.org/TR/accname-1.2/ from aria-labelledby, aria-label, labels, alt text and content.
+    pub fn accessible_name(&self) -> String {
+        accname::accessible_name(&self.0)
+    }
+
+    /// The accessible description of the element, from aria-describedby, aria-description or its title.
+    pub fn accessible_description(&self) -> String {
+        accname::accessible_description(&self.0)
+    }
+
     /// Gets the html string of the element.
     pub fn as_html_string(&self) -> String {
         self.0.outer_html()
     }
+
+    /// The document of a same-origin iframe, query it like any other document,
+    /// i.e `preview.content_document().unwrap().get_by_text("Title")`.
+    /// Returns None when the element isn't an iframe or the iframe's document belongs to another origin.
+    pub fn content_document(&self) -> Option<FrameDocument> {
+        query_root::frame_document(&self.0).map(FrameDocument)
+    }
+
+    /// The current values of the controls of a <form> by name, the same values submitting it would send.
+    /// Like FormData: disabled controls, unchecked checkboxes and radios, and controls without a name are left out,
+    /// a name used by several controls or a multiple select has several values, files are listed by file name.
+    /// Returns an empty map if the element isn't a form.
+    pub fn form_values(&self) -> BTreeMap<String, Vec<String>> {
+        form::form_values(&self.0)
+    }
+
+    /// The values of a <form> deserialized into your own type, see form_values.
+    /// Multiple selects and names shared by several controls, i.e a checkbox group, deserialize as a list of strings,
+    /// even when nothing or one thing is picked. Every other name deserializes as a string, missing when it has no value,
+    /// i.e `let login: Login = form.form_values_as().unwrap()`.
+    pub fn form_values_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(form::form_values_json(&self.0))
+    }
+
+    /// Fill in a <form> the way a user would, from a struct or map of field names to values,
+    /// i.e `form.fill_form(&Checkout { email: "ada@example.com".into(), newsletter: true })`.
+    /// Fields are found by name, or by label when no control has that name. Text fields are typed into a key at a time,
+    /// number, date and other fields that only take a complete value get it at once,
+    /// selects have the options matching by value or text selected, checkboxes and radios are clicked when they need to change.
+    /// Booleans check or uncheck a checkbox, arrays fill a checkbox group or a multiple select, None values are skipped.
+    /// Fails with `CannotFill` naming the field when no control has that name or label, a control it changes
+    /// is disabled or read only, no radio or option has the value, a value is an object or a list for a single value control,
+    /// or the values don't serialize into an object.
+    pub fn fill_form<T: Serialize>(&self, values: &T) -> Result<(), GetOneError> {
+        let values = serde_json::to_value(values)
+            .map_err(|err| GetOneError::cannot_fill("the form", err))?;
+        form::fill_form(self, values)
+    }
+
+    /// The absolute url a link navigates to, its href resolved against the document's base url, which a <base> sets.
+    /// Returns None if the element has no href or it isn't a valid url.
+    pub fn href_resolved(&self) -> Option<String> {
+        let href = self
+            .0
+            .get_attribute("href")
+            .or_else(|| self.0.get_attribute("xlink:href"))?;
+        let base = self.0.base_uri().ok().flatten()?;
+        web_sys::Url::new_with_base(href.trim(), &base)
+            .ok()
+            .map(|url| url.href())
+    }
+
+    /// The cell of a table at the crossing of the row and column whose headers match,
+    /// i.e `table.cell("Ada", "Age")` in a table of people.
+    /// Rows are matched by their row header cells or first cell, columns by their column header cells or first row.
+    /// Works for <table> and elements with role table, grid or treegrid.
+    pub fn cell<R: Into<TextMatch>, C: Into<TextMatch>>(
+        &self,
+        row_header: R,
+        column_header: C,
+    ) -> Result<TestElement, GetOneError> {
+        let row_header = row_header.into();
+        let column_header = column_header.into();
+        match table::Table::new(&self.0) {
+            Some(table) => table.cell(&row_header, &column_header),
+            None => Err(GetOneError::not_found(
+                "cell",
+                format!("row {row_header}, column {column_header} of a non table element"),
+            )),
+        }
+    }
+
+    /// The row of a table with a cell whose text matches, i.e the row of a user in a table of users.
+    pub fn row_by_text<M: Into<TextMatch>>(&self, text: M) -> Result<TestElement, GetOneError> {
+        let text = text.into();
+        get_one_inner(self.rows_by_text(text.clone()), "row_by_text", text)
+    }
+
+    /// Every row of a table with a cell whose text matches, see row_by_text.
+    /// Returns an empty list if the element isn't a table.
+    pub fn rows_by_text<M: Into<TextMatch>>(&self, text: M) -> Vec<TestElement> {
+        let text = text.into();
+        table::Table::new(&self.0)
+            .map(|table| table.rows_by_text(&text))
+            .unwrap_or_default()
+    }
+
+    /// The text of every cell of a table by row, a cell spanning several rows or columns is repeated in each.
+    /// Returns an empty grid if the element isn't a table.
+    pub fn to_grid(&self) -> Vec<Vec<String>> {
+        table::Table::new(&self.0)
+            .map(|table| table.to_grid())
+            .unwrap_or_default()
+    }
+
+    fn root(&self) -> QueryRoot {
+        QueryRoot::Element(self.0.clone())
+    }
+}
+
+impl From<Element> for TestElement {
+    fn from(value: Element) -> Self {
+        Self(value)
+    }
 }
 
 impl From<HtmlElement> for TestElement {
     fn from(value: HtmlElement) -> Self {
-        Self(value)
+        Self(value.into())
     }
 }
 
 impl Deref for TestElement {
-    type Target = web_sys::HtmlElement;
+    type Target = Element;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -162,10 +786,23 @@ impl Deref for TestElement {
 }
 
 pub struct DocumentWrapper<'a>(pub &'a Document);
+/// The document of an iframe, see `TestElement::content_document`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameDocument(pub Document);
+impl HoldsDocument for FrameDocument {
+    fn document(&self) -> DocumentWrapper<'_> {
+        DocumentWrapper(&self.0)
+    }
+}
 impl DocumentWrapper<'_> {
     pub fn body_string(&self) -> String {
         self.0.body().unwrap().outer_html()
     }
+
+    /// The headings of the document nested by level, see `Outline`.
+    pub fn outline(&self) -> Outline {
+        Outline::from_elements(QueryRoot::Document(self.0.clone()).all_elements(Scope::default()))
+    }
 }
 impl Deref for DocumentWrapper<'_> {
     type Target = Document;
@@ -173,412 +810,697 @@ impl Deref for DocumentWrapper<'_> {
         self.0
     }
 }
-fn get_one_inner<S: AsRef<str>>(
+fn get_one_inner<S: ToString>(
     list: Vec<TestElement>,
     method: &'static str,
     ident: S,
 ) -> Result<TestElement, GetOneError> {
     if list.len() > 1 {
-        Err(GetOneError::more_than_one(
-            method,
-            ident.as_ref().to_string(),
-        ))
+        Err(GetOneError::more_than_one(method, ident.to_string()))
     } else if list.is_empty() {
-        Err(GetOneError::not_found(method, ident.as_ref().to_string()))
+        Err(GetOneError::not_found(method, ident.to_string()))
     } else {
         Ok(list.first().cloned().unwrap())
     }
 }
-impl DomQuery for DocumentWrapper<'_> {
-    fn get_by_text<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(self.get_all_by_text(text.as_ref()), "by_text", text)
+/// The elements labelled by the text, or the error explaining what's wrong with a <label> of that text when there are none.
+fn labelled_elements<Q: DomQuery + ?Sized>(
+    query: &Q,
+    text: &TextMatch,
+    options: QueryOptions,
+) -> Result<Vec<TestElement>, GetOneError> {
+    let list = query.get_all_by_label_with(text.clone(), options.clone());
+    if list.is_empty() {
+        if let Some(err) =
+            label::unassociated_label_error(query.get_all_by_text_with(text.clone(), options))
+        {
+            return Err(err);
+        }
     }
-    fn get_all_by_text<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        get_all_text_nodes(self.0)
-            .find_parents_of_matching_text(text)
-            .into_iter()
-            .map(TestElement)
-            .collect()
+    Ok(list)
+}
+fn query_one_inner<S: ToString>(
+    list: Vec<TestElement>,
+    method: &'static str,
+    ident: S,
+) -> Result<Option<TestElement>, GetOneError> {
+    if list.len() > 1 {
+        Err(GetOneError::more_than_one(method, ident.to_string()))
+    } else {
+        Ok(list.first().cloned())
     }
-
-    fn get_by_text_contains<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(
-            self.get_all_by_text_contains(text.as_ref()),
-            "by_text_contains",
-            text,
-        )
+}
+fn find_all_inner<S: ToString>(
+    list: Vec<TestElement>,
+    method: &'static str,
+    ident: S,
+) -> Result<Vec<TestElement>, GetOneError> {
+    if list.is_empty() {
+        Err(GetOneError::not_found(method, ident.to_string()))
+    } else {
+        Ok(list)
     }
-
-    fn get_all_by_text_contains<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        get_all_text_nodes(self.0)
-            .find_parents_of_containing_text(text)
-            .into_iter()
-            .map(TestElement)
-            .collect()
+}
+/// What a get_all_by_X_with returns, a list, or a result for the queries whose input can be invalid i.e by_selector.
+trait ElementList {
+    fn into_result(self) -> Result<Vec<TestElement>, GetOneError>;
+}
+impl ElementList for Vec<TestElement> {
+    fn into_result(self) -> Result<Vec<TestElement>, GetOneError> {
+        Ok(self)
     }
-
-    fn get_by_id<S: AsRef<str>>(&self, id: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(self.get_all_by_id(id.as_ref()), "by_id", id)
+}
+impl ElementList for Result<Vec<TestElement>, GetOneError> {
+    fn into_result(self) -> Result<Vec<TestElement>, GetOneError> {
+        self
     }
-    fn get_all_by_id<S: AsRef<str>>(&self, id: S) -> Vec<TestElement> {
-        let tag_names = self.0.get_elements_by_tag_name("*");
-        let mut list = Vec::new();
-        for i in 0..tag_names.length() {
-            let item = tag_names.item(i).unwrap();
-            if item.id() == id.as_ref() {
-                if let Ok(item) = item.dyn_into::<HtmlElement>() {
-                    list.push(item.into())
-                }
-            }
-        }
-        list
+}
+impl DomQuery for DocumentWrapper<'_> {
+    fn get_all_by_text_with<M: Into<TextMatch>>(
+        &self,
+        text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_text_with(text, options)
     }
 
-    fn get_by_id_contains<S: AsRef<str>>(&self, id: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(
-            self.get_all_by_id_contains(id.as_ref()),
-            "by_id_contains",
-            id,
-        )
+    fn get_all_by_id_with<M: Into<TextMatch>>(
+        &self,
+        id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_id_with(id, options)
     }
 
-    fn get_all_by_id_contains<S: AsRef<str>>(&self, id: S) -> Vec<TestElement> {
-        let tag_names = self.0.get_elements_by_tag_name("*");
-        let mut list = Vec::new();
-        for i in 0..tag_names.length() {
-            let item = tag_names.item(i).unwrap();
-            if item.id().contains(id.as_ref()) {
-                if let Ok(item) = item.dyn_into::<HtmlElement>() {
-                    list.push(item.into());
-                }
-            }
-        }
-        list
+    fn get_all_by_test_id_with<M: Into<TextMatch>>(
+        &self,
+        test_id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_test_id_with(test_id, options)
     }
 
-    fn get_by_label<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(self.get_all_by_label(text.as_ref()), "by_label", text)
+    fn get_all_by_label_with<M: Into<TextMatch>>(
+        &self,
+        text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_label_with(text, options)
     }
 
-    fn get_all_by_label<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        let mut list = Vec::new();
-        let html_fors = self
-            .get_all_by_text(text)
-            .into_iter()
-            .map(|e| {
-                e.0.dyn_into::<web_sys::HtmlLabelElement>()
-                    .map(|label| label.html_for())
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
-        for html_for in html_fors {
-            list.push(self.get_by_id(&html_for).unwrap())
-        }
-        list
+    fn get_all_by_display_value_with<M: Into<TextMatch>>(
+        &self,
+        value: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_display_value_with(value, options)
     }
 
-    fn get_by_label_contains<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(
-            self.get_all_by_label_contains(text.as_ref()),
-            "by_label",
-            text,
-        )
+    fn get_all_by_role_with<S: AsRef<str>>(
+        &self,
+        role: S,
+        options: RoleOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_role_with(role, options)
     }
 
-    fn get_all_by_label_contains<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        let mut list = Vec::new();
-        let html_fors = self
-            .get_all_by_text_contains(text)
-            .into_iter()
-            .map(|e| {
-                e.0.dyn_into::<web_sys::HtmlLabelElement>()
-                    .map(|label| label.html_for())
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
-        for html_for in html_fors {
-            list.push(self.get_by_id(html_for.as_str()).unwrap())
-        }
-        list
+    fn get_all_by_alt_text_with<M: Into<TextMatch>>(
+        &self,
+        alt_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_alt_text_with(alt_text, options)
     }
 
-    fn get_by_display_value<S: AsRef<str>>(&self, value: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(
-            self.get_all_by_display_value(value.as_ref()),
-            "by_display_value",
-            value,
-        )
+    fn get_all_by_title_with<M: Into<TextMatch>>(
+        &self,
+        title: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_title_with(title, options)
     }
 
-    fn get_all_by_display_value<S: AsRef<str>>(&self, value: S) -> Vec<TestElement> {
-        let elements = self
-            .0
-            .query_selector_all("input, textarea, select")
-            .unwrap();
-        let mut list = Vec::new();
-        for i in 0..elements.length() {
-            let item = elements.item(i).unwrap().dyn_into::<HtmlElement>().unwrap();
-            if let Some(ref_item) = item.dyn_ref::<web_sys::HtmlTextAreaElement>() {
-                if ref_item.value() == value.as_ref() {
-                    list.push(TestElement(item));
-                }
-            } else if let Some(ref_item) = item.dyn_ref::<web_sys::HtmlInputElement>() {
-                if ref_item.value() == value.as_ref() {
-                    list.push(TestElement(item));
-                }
-            } else if let Ok(item) = item.dyn_into::<web_sys::HtmlSelectElement>() {
-                if item.value() == value.as_ref() {
-                    list.push(TestElement(item.into()));
-                }
-            }
-        }
-        list
+    fn get_all_by_placeholder_text_with<M: Into<TextMatch>>(
+        &self,
+        placeholder_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone())
+            .get_all_by_placeholder_text_with(placeholder_text, options)
     }
 
-    fn get_by_role<S: AsRef<str>>(&self, role: S) -> Result<TestElement, GetOneError> {
-        get_one_inner(self.get_all_by_role(role.as_ref()), "by_role", role)
+    fn get_all_by_name_with<M: Into<TextMatch>>(
+        &self,
+        name: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        QueryRoot::Document(self.0.clone()).get_all_by_name_with(name, options)
     }
 
-    fn get_all_by_role<S: AsRef<str>>(&self, role: S) -> Vec<TestElement> {
-        let selector = format!("[role='{}']", role.as_ref());
-        let elements = self.0.query_selector_all(&selector).unwrap();
-        let mut list = Vec::new();
-        for i in 0..elements.length() {
-            list.push(
-                elements
-                    .item(i)
-                    .unwrap()
-                    .dyn_into::<HtmlElement>()
-                    .unwrap()
-                    .into(),
-            )
-        }
-        list
+    fn get_all_by_selector_with(
+        &self,
+        selector: &str,
+        options: QueryOptions,
+    ) -> Result<Vec<TestElement>, GetOneError> {
+        QueryRoot::Document(self.0.clone()).get_all_by_selector_with(selector, options)
     }
+}
 
-    fn get_by_placeholder_text<S: AsRef<str>>(
+/// Querying an element only searches the subtree under it,
+/// i.e `render.get_all_by_role("row")[2].get_by_role_with("button", RoleOptions { name: Some("Delete".into()), ..Default::default() })`.
+impl DomQuery for TestElement {
+    fn get_all_by_text_with<M: Into<TextMatch>>(
         &self,
-        placeholder_text: S,
-    ) -> Result<TestElement, GetOneError> {
-        get_one_inner(
-            self.get_all_by_placeholder_text(placeholder_text.as_ref()),
-            "by_role",
-            placeholder_text,
-        )
+        text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_text_with(text, options)
     }
 
-    fn get_all_by_placeholder_text<S: AsRef<str>>(&self, placeholder_text: S) -> Vec<TestElement> {
-        let elements = self.0.get_elements_by_tag_name("input, textarea");
-        let mut list = Vec::new();
-
-        for i in 0..elements.length() {
-            let item = elements.item(i).unwrap().dyn_into::<HtmlElement>().unwrap();
-            if let Some(ref_item) = item.dyn_ref::<web_sys::HtmlTextAreaElement>() {
-                if ref_item.placeholder() == placeholder_text.as_ref() {
-                    list.push(TestElement(item));
-                }
-            } else if let Some(ref_item) = item.dyn_ref::<web_sys::HtmlInputElement>() {
-                if ref_item.placeholder() == placeholder_text.as_ref() {
-                    list.push(TestElement(item));
-                }
-            }
-        }
-        list
+    fn get_all_by_id_with<M: Into<TextMatch>>(
+        &self,
+        id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_id_with(id, options)
     }
 
-    fn get_by_placeholder_text_contains<S: AsRef<str>>(
+    fn get_all_by_test_id_with<M: Into<TextMatch>>(
         &self,
-        placeholder_text: S,
-    ) -> Result<TestElement, GetOneError> {
-        get_one_inner(
-            self.get_all_by_placeholder_text_contains(placeholder_text.as_ref()),
-            "by_role",
-            placeholder_text,
-        )
+        test_id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_test_id_with(test_id, options)
     }
 
-    fn get_all_by_placeholder_text_contains<S: AsRef<str>>(
+    fn get_all_by_label_with<M: Into<TextMatch>>(
         &self,
-        placeholder_text: S,
+        text: M,
+        options: QueryOptions,
     ) -> Vec<TestElement> {
-        let elements = self.0.get_elements_by_tag_name("input, textarea");
-        let mut list = Vec::new();
+        self.root().get_all_by_label_with(text, options)
+    }
 
-        for i in 0..elements.length() {
-            let item = elements.item(i).unwrap().dyn_into::<HtmlElement>().unwrap();
+    fn get_all_by_display_value_with<M: Into<TextMatch>>(
+        &self,
+        value: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_display_value_with(value, options)
+    }
 
-            if let Some(ref_item) = item.dyn_ref::<web_sys::HtmlTextAreaElement>() {
-                if ref_item.placeholder().contains(placeholder_text.as_ref()) {
-                    list.push(TestElement(item));
-                }
-            } else if let Some(ref_item) = item.dyn_ref::<web_sys::HtmlInputElement>() {
-                if ref_item.placeholder().contains(placeholder_text.as_ref()) {
-                    list.push(TestElement(item));
-                }
-            }
-        }
-        list
+    fn get_all_by_role_with<S: AsRef<str>>(
+        &self,
+        role: S,
+        options: RoleOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_role_with(role, options)
     }
-}
 
-fn find_all_text_nodes(node: &Node, text_nodes: &mut Vec<Node>) {
-    if node.node_type() == Node::TEXT_NODE {
-        text_nodes.push(node.clone());
-    } else if node.node_type() == Node::ELEMENT_NODE {
-        let list = node.child_nodes();
-        (0..list.length()).for_each(|i| {
-            let child = list.get(i).unwrap();
-            find_all_text_nodes(&child, text_nodes);
-        });
+    fn get_all_by_alt_text_with<M: Into<TextMatch>>(
+        &self,
+        alt_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_alt_text_with(alt_text, options)
     }
-}
 
-fn get_all_text_nodes(document: &Document) -> TextNodes {
-    let mut text_nodes = Vec::new();
-    let body = document.body().expect("Document should have a body");
+    fn get_all_by_title_with<M: Into<TextMatch>>(
+        &self,
+        title: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_title_with(title, options)
+    }
 
-    find_all_text_nodes(&body.into(), &mut text_nodes);
-    TextNodes(text_nodes)
+    fn get_all_by_placeholder_text_with<M: Into<TextMatch>>(
+        &self,
+        placeholder_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root()
+            .get_all_by_placeholder_text_with(placeholder_text, options)
+    }
+
+    fn get_all_by_name_with<M: Into<TextMatch>>(
+        &self,
+        name: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.root().get_all_by_name_with(name, options)
+    }
+
+    fn get_all_by_selector_with(
+        &self,
+        selector: &str,
+        options: QueryOptions,
+    ) -> Result<Vec<TestElement>, GetOneError> {
+        self.root().get_all_by_selector_with(selector, options)
+    }
 }
-struct TextNodes(Vec<Node>);
-impl TextNodes {
-    fn find_parents_of_matching_text<S: AsRef<str>>(&self, text: S) -> Vec<HtmlElement> {
-        let mut list = vec![];
-        for node in self.0.iter() {
-            if let Some(element) = node.parent_element() {
-                if let Some(html_element) = element.dyn_ref::<HtmlElement>() {
-                    if html_element.inner_text() == text.as_ref() {
-                        list.push(html_element.clone());
-                    }
-                }
+
+impl DomQuery for QueryRoot {
+    fn get_all_by_text_with<M: Into<TextMatch>>(
+        &self,
+        text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        get_all_text_nodes(self, options.scope())
+            .find_parents_of_matching_text(&text.into(), &options)
+            .into_iter()
+            .filter(|element| options.includes(element))
+            .collect()
+    }
+
+    fn get_all_by_id_with<M: Into<TextMatch>>(
+        &self,
+        id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        get_all_by_attribute(self, |item| Some(item.id()), &id.into(), &options)
+    }
+
+    fn get_all_by_test_id_with<M: Into<TextMatch>>(
+        &self,
+        test_id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        let attribute = get_config().test_id_attribute;
+        get_all_by_attribute(
+            self,
+            |item| item.get_attribute(&attribute),
+            &test_id.into(),
+            &options,
+        )
+    }
+
+    fn get_all_by_label_with<M: Into<TextMatch>>(
+        &self,
+        text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        let text = text.into();
+        let mut list = Vec::new();
+        for item in self.all_elements(options.scope()) {
+            let label_texts = label::label_texts(&item);
+            if label_texts.is_empty() {
+                continue;
+            }
+            let item = TestElement(item);
+            if options.includes(&item)
+                && label_texts
+                    .iter()
+                    .any(|label| text.matches(&options.normalize(label), &item))
+            {
+                list.push(item);
             }
         }
         list
     }
-    fn find_parents_of_containing_text<S: AsRef<str>>(&self, text: S) -> Vec<HtmlElement> {
-        let mut list = vec![];
-        for node in self.0.iter() {
-            if node
-                .text_content()
-                .expect("Text node to have text content,")
-                .contains(text.as_ref())
+
+    fn get_all_by_display_value_with<M: Into<TextMatch>>(
+        &self,
+        value: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        let value = value.into();
+        let mut list = Vec::new();
+        for item in self.query_selector_all("input, textarea, select", options.scope()) {
+            let Some(display_values) = display_values(&item) else {
+                continue;
+            };
+            let item = TestElement(item);
+            if options.includes(&item)
+                && display_values
+                    .iter()
+                    .any(|display_value| value.matches(&options.normalize(display_value), &item))
             {
-                if let Some(element) = node.parent_element() {
-                    if let Some(html_element) = element.dyn_ref::<HtmlElement>() {
-                        if html_element.inner_text().contains(text.as_ref()) {
-                            list.push(html_element.clone());
-                        }
-                    }
-                }
+                list.push(item);
             }
         }
         list
     }
-}
-pub trait HoldsDocument {
-    fn document(&self) -> DocumentWrapper;
-}
 
-impl<T> DomQuery for T
-where
-    T: HoldsDocument,
-{
-    fn get_by_text<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_text(text)
+    fn get_all_by_role_with<S: AsRef<str>>(
+        &self,
+        role: S,
+        options: RoleOptions,
+    ) -> Vec<TestElement> {
+        let mut list = Vec::new();
+        for item in self.all_elements(options.scope()) {
+            if role::element_role(&item).as_deref() != Some(role.as_ref()) {
+                continue;
+            }
+            let item = TestElement(item);
+            if options.matches(&item) {
+                list.push(item);
+            }
+        }
+        list
     }
 
-    fn get_all_by_text<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        self.document().get_all_by_text(text)
+    fn get_all_by_alt_text_with<M: Into<TextMatch>>(
+        &self,
+        alt_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        let alt_text = alt_text.into();
+        get_all_alt_text_elements(self, options.scope())
+            .into_iter()
+            .map(|(alt, element)| (alt, TestElement(element)))
+            .filter(|(alt, element)| {
+                options.includes(element) && alt_text.matches(&options.normalize(alt), element)
+            })
+            .map(|(_, element)| element)
+            .collect()
+    }
+
+    fn get_all_by_title_with<M: Into<TextMatch>>(
+        &self,
+        title: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        let title = title.into();
+        let mut list: Vec<TestElement> = Vec::new();
+        for item in self.query_selector_all("[title], svg > title", options.scope()) {
+            let is_svg_title = item.tag_name() == "title";
+            let text = if is_svg_title {
+                item.text_content().unwrap_or_default()
+            } else {
+                item.get_attribute("title").unwrap_or_default()
+            };
+            // An svg title names the svg it's in.
+            let element = if is_svg_title {
+                item.parent_element()
+            } else {
+                Some(item)
+            };
+            if let Some(element) = element {
+                let element = TestElement(element);
+                if options.includes(&element)
+                    && title.matches(&options.normalize(&text), &element)
+                    && !list.contains(&element)
+                {
+                    list.push(element);
+                }
+            }
+        }
+        list
     }
 
-    fn get_by_text_contains<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_text_contains(text)
+    fn get_all_by_placeholder_text_with<M: Into<TextMatch>>(
+        &self,
+        placeholder_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        let placeholder_text = placeholder_text.into();
+        let mut list = Vec::new();
+
+        for item in self.query_selector_all("input, textarea", options.scope()) {
+            let placeholder = if let Some(ref_item) =
+                cast::html_tag::<web_sys::HtmlTextAreaElement>(&item, "textarea")
+            {
+                ref_item.placeholder()
+            } else if let Some(ref_item) =
+                cast::html_tag::<web_sys::HtmlInputElement>(&item, "input")
+            {
+                ref_item.placeholder()
+            } else {
+                continue;
+            };
+            let item = TestElement(item);
+            if options.includes(&item)
+                && placeholder_text.matches(&options.normalize(&placeholder), &item)
+            {
+                list.push(item);
+            }
+        }
+        list
     }
 
-    fn get_all_by_text_contains<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        self.document().get_all_by_text_contains(text)
+    fn get_all_by_name_with<M: Into<TextMatch>>(
+        &self,
+        name: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        get_all_by_attribute(
+            self,
+            |item| {
+                form::is_form_control(item)
+                    .then(|| item.get_attribute("name"))
+                    .flatten()
+            },
+            &name.into(),
+            &options,
+        )
     }
 
-    fn get_by_id<S: AsRef<str>>(&self, id: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_id(id)
+    fn get_all_by_selector_with(
+        &self,
+        selector: &str,
+        options: QueryOptions,
+    ) -> Result<Vec<TestElement>, GetOneError> {
+        Ok(self
+            .try_query_selector_all(selector, options.scope())
+            .map_err(|_| GetOneError::invalid_selector(selector))?
+            .into_iter()
+            .map(TestElement)
+            .filter(|element| options.includes(element))
+            .collect())
     }
+}
 
-    fn get_all_by_id<S: AsRef<str>>(&self, id: S) -> Vec<TestElement> {
-        self.document().get_all_by_id(id)
+/// Every element, html, svg or mathml, whose attribute, as read by `attribute`, matches the text.
+fn get_all_by_attribute<F: Fn(&web_sys::Element) -> Option<String>>(
+    root: &QueryRoot,
+    attribute: F,
+    text: &TextMatch,
+    options: &QueryOptions,
+) -> Vec<TestElement> {
+    let mut list = Vec::new();
+    for item in root.all_elements(options.scope()) {
+        let Some(value) = attribute(&item) else {
+            continue;
+        };
+        let item = TestElement(item);
+        if options.includes(&item) && text.matches(&options.normalize(&value), &item) {
+            list.push(item);
+        }
     }
+    list
+}
 
-    fn get_by_id_contains<S: AsRef<str>>(&self, id: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_id_contains(id)
+/// What the user sees as the value of a form control, None for elements without a display value.
+/// A select shows the text of its selected options, any of them can match a multiple select.
+/// Checkboxes and radios show a check rather than their value, so they don't have one.
+fn display_values(element: &Element) -> Option<Vec<String>> {
+    if let Some(textarea) = cast::html_tag::<web_sys::HtmlTextAreaElement>(element, "textarea") {
+        Some(vec![textarea.value()])
+    } else if let Some(input) = cast::html_tag::<web_sys::HtmlInputElement>(element, "input") {
+        let input_type = input.type_();
+        if input_type == "checkbox" || input_type == "radio" {
+            return None;
+        }
+        Some(vec![input.value()])
+    } else if let Some(select) = cast::html_tag::<web_sys::HtmlSelectElement>(element, "select") {
+        let selected = select.selected_options();
+        Some(
+            (0..selected.length())
+                .filter_map(|i| selected.item(i))
+                .map(|option| option.text_content().unwrap_or_default())
+                .collect(),
+        )
+    } else {
+        None
     }
+}
 
-    fn get_all_by_id_contains<S: AsRef<str>>(&self, id: S) -> Vec<TestElement> {
-        self.document().get_all_by_id_contains(id)
+/// Elements that can carry alt text, paired with their alt attribute.
+fn get_all_alt_text_elements(root: &QueryRoot, scope: Scope) -> Vec<(String, Element)> {
+    let mut list = Vec::new();
+    for item in root.query_selector_all("[alt]", scope) {
+        let tag = item.tag_name().to_lowercase();
+        let takes_alt = match tag.as_str() {
+            "img" | "area" => true,
+            "input" => item
+                .get_attribute("type")
+                .map(|t| t.eq_ignore_ascii_case("image"))
+                .unwrap_or(false),
+            // Custom elements always contain a hyphen.
+            _ => tag.contains('-'),
+        };
+        if takes_alt {
+            let alt = item.get_attribute("alt").unwrap_or_default();
+            list.push((alt, item));
+        }
     }
+    list
+}
 
-    fn get_by_label<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_label(text)
+fn find_all_text_nodes(node: &Node, text_nodes: &mut Vec<Node>, scope: Scope) {
+    if node.node_type() == Node::TEXT_NODE {
+        text_nodes.push(node.clone());
+    } else if let Some(element) = cast::element(node) {
+        if scope.pierce_iframes {
+            if let Some(body) = query_root::frame_document(element).and_then(|d| d.body()) {
+                find_all_text_nodes(&body, text_nodes, scope);
+                return;
+            }
+        }
+        for child in query_root::children(node, scope) {
+            find_all_text_nodes(&child, text_nodes, scope);
+        }
     }
+}
+
+fn get_all_text_nodes(root: &QueryRoot, scope: Scope) -> TextNodes {
+    let mut text_nodes = Vec::new();
 
-    fn get_all_by_label<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        self.document().get_all_by_label(text)
+    find_all_text_nodes(&root.text_root(), &mut text_nodes, scope);
+    TextNodes(text_nodes)
+}
+struct TextNodes(Vec<Node>);
+impl TextNodes {
+    /// Parents of the text nodes whose inner text matches. When both an element and one of its descendants match,
+    /// only the descendant is kept, so we find the element closest to the text.
+    /// Hidden elements have no rendered text, so when they are included those match on their text content instead.
+    fn find_parents_of_matching_text(
+        &self,
+        text: &TextMatch,
+        options: &QueryOptions,
+    ) -> Vec<TestElement> {
+        let mut list: Vec<TestElement> = vec![];
+        for node in self.0.iter() {
+            if let Some(element) = node.parent_element() {
+                let element = TestElement(element);
+                if list.contains(&element) {
+                    continue;
+                }
+                let element_text = if options.hidden && role::is_inaccessible(&element) {
+                    element.text_content().unwrap_or_default()
+                } else {
+                    element.inner_text()
+                };
+                let element_text = options.normalize(&element_text);
+                if text.matches(&element_text, &element) {
+                    list.push(element);
+                }
+            }
+        }
+        list.iter()
+            .filter(|element| {
+                !list
+                    .iter()
+                    .any(|other| other != *element && element.contains(Some(other)))
+            })
+            .cloned()
+            .collect()
     }
+}
+pub trait HoldsDocument {
+    fn document(&self) -> DocumentWrapper<'_>;
+}
 
-    fn get_by_label_contains<S: AsRef<str>>(&self, text: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_label_contains(text)
+impl<T> DomQuery for T
+where
+    T: HoldsDocument,
+{
+    fn get_all_by_text_with<M: Into<TextMatch>>(
+        &self,
+        text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.document().get_all_by_text_with(text, options)
     }
 
-    fn get_all_by_label_contains<S: AsRef<str>>(&self, text: S) -> Vec<TestElement> {
-        self.document().get_all_by_label_contains(text)
+    fn get_all_by_id_with<M: Into<TextMatch>>(
+        &self,
+        id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.document().get_all_by_id_with(id, options)
     }
 
-    fn get_by_display_value<S: AsRef<str>>(&self, value: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_display_value(value)
+    fn get_all_by_test_id_with<M: Into<TextMatch>>(
+        &self,
+        test_id: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.document().get_all_by_test_id_with(test_id, options)
     }
 
-    fn get_all_by_display_value<S: AsRef<str>>(&self, value: S) -> Vec<TestElement> {
-        self.document().get_all_by_display_value(value)
+    fn get_all_by_label_with<M: Into<TextMatch>>(
+        &self,
+        text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.document().get_all_by_label_with(text, options)
     }
 
-    fn get_by_role<S: AsRef<str>>(&self, role: S) -> Result<TestElement, GetOneError> {
-        self.document().get_by_role(role)
+    fn get_all_by_display_value_with<M: Into<TextMatch>>(
+        &self,
+        value: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.document()
+            .get_all_by_display_value_with(value, options)
     }
 
-    fn get_all_by_role<S: AsRef<str>>(&self, role: S) -> Vec<TestElement> {
-        self.document().get_all_by_role(role)
+    fn get_all_by_role_with<S: AsRef<str>>(
+        &self,
+        role: S,
+        options: RoleOptions,
+    ) -> Vec<TestElement> {
+        self.document().get_all_by_role_with(role, options)
     }
 
-    fn get_by_placeholder_text<S: AsRef<str>>(
+    fn get_all_by_alt_text_with<M: Into<TextMatch>>(
         &self,
-        placeholder_text: S,
-    ) -> Result<TestElement, GetOneError> {
-        self.document().get_by_placeholder_text(placeholder_text)
+        alt_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.document().get_all_by_alt_text_with(alt_text, options)
     }
 
-    fn get_all_by_placeholder_text<S: AsRef<str>>(&self, placeholder_text: S) -> Vec<TestElement> {
-        self.document()
-            .get_all_by_placeholder_text(placeholder_text)
+    fn get_all_by_title_with<M: Into<TextMatch>>(
+        &self,
+        title: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
+        self.document().get_all_by_title_with(title, options)
     }
 
-    fn get_by_placeholder_text_contains<S: AsRef<str>>(
+    fn get_all_by_placeholder_text_with<M: Into<TextMatch>>(
         &self,
-        placeholder_text: S,
-    ) -> Result<TestElement, GetOneError> {
+        placeholder_text: M,
+        options: QueryOptions,
+    ) -> Vec<TestElement> {
         self.document()
-            .get_by_placeholder_text_contains(placeholder_text)
+            .get_all_by_placeholder_text_with(placeholder_text, options)
     }
 
-    fn get_all_by_placeholder_text_contains<S: AsRef<str>>(
+    fn get_all_by_name_with<M: Into<TextMatch>>(
         &self,
-        placeholder_text: S,
+        name: M,
+        options: QueryOptions,
     ) -> Vec<TestElement> {
-        self.document()
-            .get_all_by_placeholder_text_contains(placeholder_text)
+        self.document().get_all_by_name_with(name, options)
+    }
+
+    fn get_all_by_selector_with(
+        &self,
+        selector: &str,
+        options: QueryOptions,
+    ) -> Result<Vec<TestElement>, GetOneError> {
+        self.document().get_all_by_selector_with(selector, options)
     }
 }
 
 #[cfg(all(test, feature = "unit_tests"))]
 pub mod test {
     use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen::JsCast;
     use wasm_bindgen_test::*;
     wasm_bindgen_test_configure!(run_in_browser);
 
@@ -596,7 +1518,7 @@ pub mod test {
             .unwrap()
             .append_child(div.dyn_ref::<Node>().unwrap())
             .unwrap();*/
-        let text_nodes = get_all_text_nodes(&document);
+        let text_nodes = get_all_text_nodes(&QueryRoot::Document(document.0.clone()), Scope::default());
          panic!("{}",text_nodes.0.into_iter().filter_map(|n|if n.text_content().unwrap().contains("hello"){
             Some(n.text_content().unwrap())
         }else {None}).collect::<Vec<String>>().join("\nSEP\n"));
@@ -606,20 +1528,11 @@ pub mod test {
     pub fn find_parents_of_matching_text() {
         let document = web_sys::window().unwrap().document().unwrap();
         let document = DocumentWrapper(&document);
-        // create test wrapper
-        let wrapper: web_sys::Element = document.create_element("div").unwrap();
-        let div = document.create_element("div").unwrap();
-        div.clone()
-            .unchecked_into::<HtmlElement>()
-            .set_inner_text("hello");
-        wrapper.append_child(&div.into()).unwrap();
-        document
-            .body()
-            .unwrap()
-            .append_child(&wrapper.clone().into())
-            .unwrap();
-        let text_nodes = get_all_text_nodes(&document);
-        let results = text_nodes.find_parents_of_matching_text("hello");
+        let _mounted = Mounted::new("<div>hello</div>");
+        let text_nodes =
+            get_all_text_nodes(&QueryRoot::Document(document.0.clone()), Scope::default());
+        let results = text_nodes
+            .find_parents_of_matching_text(&TextMatch::exact("hello"), &QueryOptions::default());
         if results.len() != 1 {
             panic!(
                 "{}",
@@ -635,20 +1548,11 @@ pub mod test {
     pub fn find_parents_of_containing_text() {
         let document = web_sys::window().unwrap().document().unwrap();
         let document = DocumentWrapper(&document);
-        // create test wrapper
-        let wrapper: web_sys::Element = document.create_element("div").unwrap();
-        let div = document.create_element("div").unwrap();
-        div.clone()
-            .unchecked_into::<HtmlElement>()
-            .set_inner_text("other");
-        wrapper.append_child(&div.into()).unwrap();
-        document
-            .body()
-            .unwrap()
-            .append_child(&wrapper.into())
-            .unwrap();
-        let text_nodes = get_all_text_nodes(&document);
-        let results = text_nodes.find_parents_of_containing_text("other");
+        let _mounted = Mounted::new("<div>other</div>");
+        let text_nodes =
+            get_all_text_nodes(&QueryRoot::Document(document.0.clone()), Scope::default());
+        let results = text_nodes
+            .find_parents_of_matching_text(&TextMatch::contains("other"), &QueryOptions::default());
         if results.len() != 1 {
             panic!(
                 "{}",
@@ -660,4 +1564,723 @@ pub mod test {
             )
         }
     }
+    #[wasm_bindgen_test]
+    pub fn get_by_test_id_with_configured_attribute() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<span data-testid='test_id_default'></span><span data-cy='test_id_custom'></span>",
+        );
+        let document = DocumentWrapper(&document);
+        assert!(document.get_by_test_id("test_id_default").is_ok());
+        assert!(document.get_by_test_id("test_id_custom").is_not_found());
+        configure(|config| config.test_id_attribute = String::from("data-cy"));
+        let custom = document.get_by_test_id("test_id_custom");
+        configure(|config| *config = Config::default());
+        assert!(custom.is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn get_by_alt_text_matches_images_areas_and_image_inputs() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<img alt='alt_logo' src=''/>\
+            <img alt='alt_mapped' usemap='#alt_map'/><map name='alt_map'><area alt='alt_area' href='/'/></map>\
+            <map name='alt_unused_map'><area alt='alt_unmapped_area' href='/'/></map>\
+            <input type='image' alt='alt_submit'/>\
+            <img alt='alt_twice'/><img alt='alt_twice'/>\
+            <div alt='alt_div'></div>",
+        );
+        let document = DocumentWrapper(&document);
+        assert_eq!(
+            document.get_by_alt_text("alt_logo").unwrap().tag_name(),
+            "IMG"
+        );
+        assert_eq!(
+            document.get_by_alt_text("alt_area").unwrap().tag_name(),
+            "AREA"
+        );
+        assert!(document.get_by_alt_text("alt_unmapped_area").is_not_found());
+        assert_eq!(
+            document.get_by_alt_text("alt_submit").unwrap().tag_name(),
+            "INPUT"
+        );
+        assert!(document.get_by_alt_text("alt_div").is_not_found());
+        assert!(document.get_by_alt_text("alt_missing").is_not_found());
+        assert!(document.get_by_alt_text("alt_twice").is_more_than_one());
+        assert_eq!(document.get_all_by_alt_text("alt_twice").len(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn get_by_title_finds_svg_titles() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<span title='title_attribute'></span>\
+            <button id='title_icon_button'><svg><title>title_svg</title></svg></button>",
+        );
+        let document = DocumentWrapper(&document);
+        assert!(document.get_by_title("title_attribute").is_ok());
+        let svg = document.get_by_title("title_svg").unwrap();
+        assert!(svg.as_svg().is_some());
+        assert_eq!(svg.parent_element().unwrap().id(), "title_icon_button");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn text_is_normalized_before_matching() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new("<p>\n  normalized\u{a0}\n   text  </p>");
+        let document = DocumentWrapper(&document);
+        assert!(document.get_by_text("normalized text").is_ok());
+        let raw = QueryOptions {
+            normalizer: Some(Normalizer::none()),
+            ..Default::default()
+        };
+        assert!(document
+            .get_by_text_with("normalized text", raw)
+            .is_not_found());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn query_by_returns_none_when_absent() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new("<span>query_twice</span><span>query_twice</span>");
+        let document = DocumentWrapper(&document);
+        assert_eq!(document.query_by_text("query_never_rendered"), Ok(None));
+        assert!(document.query_by_text("query_twice").is_more_than_one());
+    }
+
+    #[wasm_bindgen_test]
+    pub async fn find_by_waits_for_the_element() {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let mounted = Mounted::new("");
+        let wrapper = (*mounted).clone();
+        let render_later = wasm_bindgen::closure::Closure::once_into_js(move || {
+            wrapper.set_inner_html("<p>find_rendered_later</p>");
+        });
+        window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                render_later.unchecked_ref(),
+                100,
+            )
+            .unwrap();
+        let document = DocumentWrapper(&document);
+        assert!(document.find_by_text("find_rendered_later").await.is_ok());
+        let never = document
+            .find_by_text_with(
+                "find_never_rendered",
+                QueryOptions::default(),
+                WaitForOptions {
+                    timeout: 100,
+                    interval: 20,
+                },
+            )
+            .await;
+        assert!(never.is_timeout());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn queries_on_an_element_are_scoped_to_it() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<ul><li id='within_first'><button>within_delete</button></li>\
+            <li id='within_second'><button>within_delete</button></li></ul>",
+        );
+        let document = DocumentWrapper(&document);
+        assert!(document.get_by_text("within_delete").is_more_than_one());
+        let second = document.get_by_id("within_second").unwrap();
+        let button = second.get_by_text("within_delete").unwrap();
+        assert!(second.contains(Some(&button)));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn get_by_label_follows_every_association() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<label>label_wrapped <input id='label_wrapped'/></label>\
+            <span id='label_first'>label_first</span><span id='label_last'>label_last</span>\
+            <input id='label_referenced' aria-labelledby='label_first label_last'/>\
+            <input id='label_aria' aria-label='label_aria'/>",
+        );
+        let document = DocumentWrapper(&document);
+        assert_eq!(
+            document.get_by_label("label_wrapped").unwrap().id(),
+            "label_wrapped"
+        );
+        let referenced = document.get_by_label("label_first label_last").unwrap();
+        assert_eq!(referenced.id(), "label_referenced");
+        assert_eq!(
+            document.get_by_label("label_last").unwrap().id(),
+            "label_referenced"
+        );
+        assert_eq!(
+            document.get_by_label("label_aria").unwrap().id(),
+            "label_aria"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn get_by_label_explains_broken_labels() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<label for='label_missing'>label_missing</label>\
+            <label for='label_div'>label_div</label><div id='label_div'></div>",
+        );
+        let document = DocumentWrapper(&document);
+        let missing = document.get_by_label("label_missing");
+        assert_eq!(
+            missing,
+            Err(GetOneError::LabelWithoutControl {
+                label: String::from("<label for=\"label_missing\">label_missing</label>"),
+                for_id: Some(String::from("label_missing")),
+            })
+        );
+        assert!(document
+            .get_by_label("label_div")
+            .is_labelled_element_not_labelable());
+        assert!(document
+            .query_by_label("label_div")
+            .is_labelled_element_not_labelable());
+        assert!(document
+            .query_by_label("label_missing")
+            .is_label_without_control());
+        assert_eq!(document.query_by_label("label_never_rendered"), Ok(None));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn hidden_elements_are_excluded_by_default() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<div style='display:none'><p>hidden_display</p></div>\
+            <p style='visibility:hidden'>hidden_visibility</p>\
+            <div inert><p>hidden_inert</p></div>\
+            <input aria-hidden='true' placeholder='hidden_aria'/>",
+        );
+        let document = DocumentWrapper(&document);
+        let include_hidden = QueryOptions {
+            hidden: true,
+            ..Default::default()
+        };
+        for text in ["hidden_display", "hidden_visibility", "hidden_inert"] {
+            assert!(document.get_by_text(text).is_not_found());
+            assert!(document
+                .get_by_text_with(text, include_hidden.clone())
+                .is_ok());
+        }
+        assert!(document
+            .get_by_placeholder_text("hidden_aria")
+            .is_not_found());
+        assert!(document
+            .get_by_placeholder_text_with("hidden_aria", include_hidden)
+            .is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn including_hidden_elements_keeps_visible_text() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new("<p>visible_first<br>visible_second</p>");
+        let document = DocumentWrapper(&document);
+        let include_hidden = QueryOptions {
+            hidden: true,
+            ..Default::default()
+        };
+        let visible = document.get_by_text("visible_first visible_second");
+        assert!(visible.is_ok());
+        assert_eq!(
+            document.get_by_text_with("visible_first visible_second", include_hidden),
+            visible
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn queries_pierce_open_shadow_roots_when_asked() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let mounted = Mounted::new("<div></div>");
+        let host = mounted.first();
+        host.set_inner_html("<span slot='label'>shadow_slotted</span>");
+        let shadow_root = host
+            .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+            .unwrap();
+        shadow_root.set_inner_html("<button><slot name='label'></slot></button><p>shadow_text</p>");
+        let document = DocumentWrapper(&document);
+        let pierce = QueryOptions {
+            pierce_shadow_dom: true,
+            ..Default::default()
+        };
+        assert!(document.get_by_text("shadow_text").is_not_found());
+        assert!(document
+            .get_by_text_with("shadow_text", pierce.clone())
+            .is_ok());
+        let host = TestElement::from(host);
+        let button = host
+            .get_by_role_with(
+                "button",
+                RoleOptions {
+                    query: pierce.clone(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(button.tag_name(), "BUTTON");
+        assert!(document.get_by_text_with("shadow_slotted", pierce).is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn labels_resolve_inside_shadow_roots() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let mounted = Mounted::new("<div></div>");
+        let shadow_root = mounted
+            .first()
+            .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+            .unwrap();
+        shadow_root.set_inner_html(
+            "<label>shadow_wrapped <input id='shadow_wrapped'/></label>\
+            <label for='shadow_for'>shadow_for_label</label><input id='shadow_for'/>\
+            <span id='shadow_name'>shadow_labelledby</span><input id='shadow_referenced' aria-labelledby='shadow_name'/>",
+        );
+        let document = DocumentWrapper(&document);
+        let pierce = QueryOptions {
+            pierce_shadow_dom: true,
+            ..Default::default()
+        };
+        for (label, id) in [
+            ("shadow_wrapped", "shadow_wrapped"),
+            ("shadow_for_label", "shadow_for"),
+            ("shadow_labelledby", "shadow_referenced"),
+        ] {
+            assert_eq!(
+                document
+                    .get_by_label_with(label, pierce.clone())
+                    .unwrap()
+                    .id(),
+                id
+            );
+        }
+        let referenced = document
+            .get_by_role_with(
+                "textbox",
+                RoleOptions {
+                    name: Some("shadow_labelledby".into()),
+                    query: pierce,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(referenced.id(), "shadow_referenced");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn queries_reach_into_same_origin_iframes() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new("<iframe id='frame_preview'></iframe>");
+        let document = DocumentWrapper(&document);
+        let preview = document.get_by_id("frame_preview").unwrap();
+        let frame_document = preview.content_document().unwrap();
+        frame_document
+            .0
+            .body()
+            .unwrap()
+            .set_inner_html("<p>frame_text</p><input placeholder='frame_input'/>");
+        assert!(frame_document.get_by_text("frame_text").is_ok());
+        assert!(document.get_by_text("frame_text").is_not_found());
+        let pierce = QueryOptions {
+            pierce_iframes: true,
+            ..Default::default()
+        };
+        assert!(document
+            .get_by_text_with("frame_text", pierce.clone())
+            .is_ok());
+        assert!(document
+            .get_by_placeholder_text_with("frame_input", pierce)
+            .is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn svg_elements_are_query_results() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted =
+            Mounted::new("<svg role='img' aria-label='svg_chart'><text>svg_label</text></svg>");
+        let document = DocumentWrapper(&document);
+        let chart = document
+            .get_by_role_with(
+                "img",
+                RoleOptions {
+                    name: Some("svg_chart".into()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(chart.as_svg().is_some());
+        assert!(chart.as_html().is_none());
+        let label = document.get_by_text("svg_label").unwrap();
+        assert_eq!(label.tag_name(), "text");
+    }
+
+    #[wasm_bindgen_test]
+    pub fn display_value_is_what_the_user_sees() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<select id='display_single'><option value='no'>display_norway</option></select>\
+            <select id='display_multiple' multiple>\
+            <option selected>display_red</option><option>display_green</option><option selected>display_blue</option>\
+            </select>\
+            <input type='checkbox' value='display_checkbox' checked/>",
+        );
+        let document = DocumentWrapper(&document);
+        assert_eq!(
+            document
+                .get_by_display_value("display_norway")
+                .unwrap()
+                .id(),
+            "display_single"
+        );
+        assert!(document.get_by_display_value("no").is_not_found());
+        assert_eq!(
+            document.get_by_display_value("display_blue").unwrap().id(),
+            "display_multiple"
+        );
+        assert!(document
+            .get_by_display_value("display_green")
+            .is_not_found());
+        assert!(document
+            .get_by_display_value("display_checkbox")
+            .is_not_found());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn selector_queries_share_the_error_handling() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<ul id='selector_list'><li class='selector_item'>one</li><li class='selector_item'>two</li></ul>",
+        );
+        let document = DocumentWrapper(&document);
+        assert_eq!(
+            document
+                .get_all_by_selector(".selector_item")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(document
+            .get_by_selector(".selector_item")
+            .is_more_than_one());
+        let list = document.get_by_selector("#selector_list").unwrap();
+        assert!(list.get_by_selector("li:first-child").is_ok());
+        assert_eq!(document.query_by_selector(".selector_missing"), Ok(None));
+        assert!(document.get_by_selector("li[").is_invalid_selector());
+        assert!(document.query_all_by_selector("li[").is_invalid_selector());
+    }
+
+    #[wasm_bindgen_test]
+    pub async fn selector_queries_have_find_versions() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<ul><li class='find_selector_item'>one</li><li class='find_selector_item'>two</li></ul>",
+        );
+        let document = DocumentWrapper(&document);
+        assert_eq!(
+            document
+                .find_all_by_selector(".find_selector_item")
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let invalid = document
+            .find_by_selector_with(
+                "li[",
+                QueryOptions::default(),
+                WaitForOptions {
+                    timeout: 100,
+                    interval: 20,
+                },
+            )
+            .await;
+        assert_eq!(
+            invalid,
+            Err(GetOneError::timeout(
+                100,
+                GetOneError::invalid_selector("li[")
+            ))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn get_by_heading_matches_level_and_text() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<h1>heading_page</h1><h2>heading_section</h2><div role='heading' aria-level='3'>heading_sub</div>\
+            <h2 hidden>heading_hidden</h2>",
+        );
+        let document = DocumentWrapper(&document);
+        assert_eq!(
+            document
+                .get_by_heading(2, "heading_section")
+                .unwrap()
+                .tag_name(),
+            "H2"
+        );
+        assert!(document.get_by_heading(1, "heading_section").is_not_found());
+        assert!(document.get_by_heading(3, "heading_sub").is_ok());
+        assert_eq!(document.query_by_heading(4, "heading_sub"), Ok(None));
+        let include_hidden = RoleOptions {
+            query: QueryOptions {
+                hidden: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(document
+            .get_by_heading_with(2, "heading_hidden", include_hidden)
+            .is_ok());
+        assert!(document.get_by_heading(2, "heading_hidden").is_not_found());
+        assert!(document
+            .outline()
+            .to_string()
+            .contains("h1 heading_page\n  h2 heading_section\n    h3 heading_sub\n"));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn table_helpers_keep_rows_and_columns() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<table id='table_users'><thead><tr><th>Name</th><th>Role</th></tr></thead>\
+            <tbody><tr><td>table_ada</td><td>Admin</td></tr><tr><td>table_bob</td><td>Viewer</td></tr></tbody></table>",
+        );
+        let document = DocumentWrapper(&document);
+        let table = document.get_by_id("table_users").unwrap();
+        assert_eq!(
+            table.cell("table_bob", "Role").unwrap().inner_text(),
+            "Viewer"
+        );
+        let row = table.row_by_text("table_ada").unwrap();
+        assert_eq!(table.rows_by_text("table_").len(), 0);
+        assert_eq!(
+            table
+                .row_by_text(TextMatch::contains("table_"))
+                .unwrap_err()
+                .to_string(),
+            "Found more than one element by row_by_text with input of text containing \"table_\", \
+            if you were expecting more than one row see rows_by_text instead."
+        );
+        assert!(row.get_by_text("Admin").is_ok());
+        assert_eq!(table.to_grid()[0], vec!["Name", "Role"]);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn form_values_follow_form_data() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Signup {
+            email: String,
+            plan: String,
+            topics: Vec<String>,
+        }
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<form id='form_signup'><input name='email' value='ada@example.com'/>\
+            <input type='radio' name='plan' value='free'/><input type='radio' name='plan' value='pro' checked/>\
+            <select name='topics' multiple><option selected>rust</option><option>go</option><option selected>wasm</option></select>\
+            <input name='form_disabled' value='x' disabled/><input type='checkbox' name='form_unchecked'/></form>",
+        );
+        let document = DocumentWrapper(&document);
+        assert_eq!(document.get_all_by_name("plan").len(), 2);
+        let form = document.get_by_id("form_signup").unwrap();
+        let values = form.form_values();
+        assert_eq!(values["email"], vec!["ada@example.com"]);
+        assert!(!values.contains_key("form_disabled"));
+        assert!(!values.contains_key("form_unchecked"));
+        let signup: Signup = form.form_values_as().unwrap();
+        assert_eq!(
+            signup,
+            Signup {
+                email: String::from("ada@example.com"),
+                plan: String::from("pro"),
+                topics: vec![String::from("rust"), String::from("wasm")],
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn form_values_keep_their_shape_with_no_or_one_value() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Preferences {
+            nickname: String,
+            theme: Option<String>,
+            remember: Option<String>,
+            colors: Vec<String>,
+            sizes: Vec<String>,
+            days: Vec<String>,
+        }
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<form id='form_preferences'><input name='nickname' value='ada'/>\
+            <input type='radio' name='theme' value='dark'/><input type='checkbox' name='remember'/>\
+            <input type='checkbox' name='colors' value='red'/><input type='checkbox' name='colors' value='blue'/>\
+            <select name='sizes' multiple><option selected>small</option><option>large</option></select>\
+            <select name='days' multiple><option>monday</option></select></form>",
+        );
+        let document = DocumentWrapper(&document);
+        let form = document.get_by_id("form_preferences").unwrap();
+        let preferences: Preferences = form.form_values_as().unwrap();
+        assert_eq!(
+            preferences,
+            Preferences {
+                nickname: String::from("ada"),
+                theme: None,
+                remember: None,
+                colors: vec![],
+                sizes: vec![String::from("small")],
+                days: vec![],
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn fill_form_types_selects_and_toggles() {
+        use std::{cell::RefCell, rc::Rc};
+        use wasm_bindgen::closure::Closure;
+        #[derive(serde::Serialize)]
+        struct Checkout {
+            email: String,
+            #[serde(rename = "Country")]
+            country: String,
+            newsletter: bool,
+            shipping: String,
+            coupon: Option<String>,
+        }
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<form id='fill_checkout'><input name='email' value='old@example.com'/>\
+            <label>Country <select><option value='no'>Norway</option><option value='se'>Sweden</option></select></label>\
+            <input type='checkbox' name='newsletter'/>\
+            <input type='radio' name='shipping' value='standard' checked/><input type='radio' name='shipping' value='express'/>\
+            <input name='coupon' value='KEEP'/></form>",
+        );
+        let document = DocumentWrapper(&document);
+        let form = document.get_by_id("fill_checkout").unwrap();
+        let typed = Rc::new(RefCell::new(0));
+        let on_keydown = Closure::<dyn FnMut()>::new({
+            let typed = typed.clone();
+            move || *typed.borrow_mut() += 1
+        });
+        form.add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref())
+            .unwrap();
+        form.fill_form(&Checkout {
+            email: String::from("ada@example.com"),
+            country: String::from("Sweden"),
+            newsletter: true,
+            shipping: String::from("express"),
+            coupon: None,
+        })
+        .unwrap();
+        assert_eq!(*typed.borrow(), "ada@example.com".len());
+        let values = form.form_values();
+        assert_eq!(values["email"], vec!["ada@example.com"]);
+        assert_eq!(values["newsletter"], vec!["on"]);
+        assert_eq!(values["shipping"], vec!["express"]);
+        assert_eq!(values["coupon"], vec!["KEEP"]);
+        assert!(document.get_by_display_value("Sweden").is_ok());
+        assert_eq!(
+            form.fill_form(&serde_json::json!({ "fill_missing": "x" })),
+            Err(GetOneError::CannotFill {
+                field: String::from("fill_missing"),
+                reason: String::from("no control has this name or label"),
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn fill_form_sets_whole_values_and_reports_what_it_cannot_fill() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<form id='fill_booking'><input type='number' name='guests'/><input type='date' name='arrival'/>\
+            <input name='fill_disabled' disabled/><textarea name='fill_readonly' readonly></textarea>\
+            <input type='radio' name='room' value='single'/><input type='radio' name='room' value='double'/>\
+            <input type='radio' name='room' value='suite_closed' disabled/><input name='fill_note' value='keep'/>\
+            <select name='meal'><option>breakfast</option></select></form>",
+        );
+        let document = DocumentWrapper(&document);
+        let form = document.get_by_id("fill_booking").unwrap();
+        form.fill_form(&serde_json::json!({ "guests": 12, "arrival": "2024-02-29" }))
+            .unwrap();
+        let values = form.form_values();
+        assert_eq!(values["guests"], vec!["12"]);
+        assert_eq!(values["arrival"], vec!["2024-02-29"]);
+        form.fill_form(&serde_json::json!({ "room": "double" }))
+            .unwrap();
+        assert_eq!(form.form_values()["room"], vec!["double"]);
+        for values in [
+            serde_json::json!({ "fill_note": { "nested": "x" } }),
+            serde_json::json!({ "fill_note": ["x", "y"] }),
+            serde_json::json!({ "room": "suite_closed" }),
+            serde_json::json!({ "fill_disabled": "x" }),
+            serde_json::json!({ "fill_readonly": "x" }),
+            serde_json::json!({ "room": "suite" }),
+            serde_json::json!({ "meal": "dinner" }),
+            serde_json::json!(["room"]),
+        ] {
+            assert!(form.fill_form(&values).is_cannot_fill());
+        }
+        assert_eq!(form.form_values()["fill_note"], vec!["keep"]);
+        let non_string_keys = BTreeMap::from([((1, 2), "x")]);
+        assert!(form.fill_form(&non_string_keys).is_cannot_fill());
+        assert_eq!(
+            form.fill_form(&serde_json::json!({ "room": "suite" })),
+            Err(GetOneError::CannotFill {
+                field: String::from("room"),
+                reason: String::from("no radio button has the value suite"),
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn links_resolve_against_the_base_url() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let base = document.create_element("base").unwrap();
+        base.set_attribute("href", "https://example.com/app/")
+            .unwrap();
+        let _base = Mounted::in_head(base);
+        let _mounted = Mounted::new(
+            "<nav><a href='settings' aria-current='page'>link_settings</a><a href='/logout'>link_logout</a><a>link_no_href</a></nav>",
+        );
+        let document = DocumentWrapper(&document);
+        let settings = document.get_by_link("link_settings").unwrap();
+        let logout = document.get_by_link("link_logout").unwrap();
+        assert_eq!(
+            settings.href_resolved().unwrap(),
+            "https://example.com/app/settings"
+        );
+        assert_eq!(
+            logout.href_resolved().unwrap(),
+            "https://example.com/logout"
+        );
+        assert!(document.get_by_link("link_no_href").is_not_found());
+        assert_eq!(document.query_by_link("link_no_href"), Ok(None));
+        let current = RoleOptions {
+            current: Some(String::from("page")),
+            ..Default::default()
+        };
+        assert_eq!(
+            document.get_by_link_with("link_settings", current).unwrap(),
+            settings
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn navigate_between_related_elements() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let _mounted = Mounted::new(
+            "<article id='nav_card'><h2>nav_title</h2><p>nav_body</p><button>nav_action</button></article>",
+        );
+        let document = DocumentWrapper(&document);
+        let title = document.get_by_text("nav_title").unwrap();
+        let card = title.closest_by_role("article").unwrap();
+        assert_eq!(card.id(), "nav_card");
+        assert_eq!(title.parent_test_element(), Some(card.clone()));
+        assert_eq!(title.siblings().len(), 2);
+        let button = title.next_by_role("button").unwrap();
+        assert_eq!(button.previous_by_role("heading"), Some(title.clone()));
+        assert!(title.previous_by_role("button").is_none());
+        let with_text = card.children_matching(|child| child.inner_text().starts_with("nav_b"));
+        assert_eq!(with_text.len(), 1);
+    }
 }
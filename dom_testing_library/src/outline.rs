@@ -0,0 +1,130 @@
+use crate::role::{element_role, heading_level, is_inaccessible};
+use crate::{accname, TestElement};
+use std::fmt;
+use web_sys::Element;
+
+/// The headings of a page nested by level, i.e an h2 sits under the h1 before it.
+/// Its Display output is one indented line per heading, so a page's structure can be asserted in one `assert_eq!`:
+/// ```text
+/// h1 Products
+///   h2 Shoes
+///   h2 Hats
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outline(pub Vec<Heading>);
+
+/// A heading of the outline and the headings nested under it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heading {
+    /// From the h1-h6 tag or aria-level, role="heading" without aria-level is level 2.
+    pub level: u32,
+    /// The accessible name of the heading.
+    pub text: String,
+    pub element: TestElement,
+    pub children: Vec<Heading>,
+}
+
+impl Outline {
+    /// Build the outline of the headings under the root, hidden headings are left out.
+    pub(crate) fn from_elements(elements: Vec<Element>) -> Self {
+        let mut roots: Vec<Heading> = Vec::new();
+        for element in elements {
+            if element_role(&element).as_deref() != Some("heading") || is_inaccessible(&element) {
+                continue;
+            }
+            let heading = Heading {
+                level: heading_level(&element).unwrap_or(2),
+                text: accname::accessible_name(&element),
+                element: TestElement(element),
+                children: Vec::new(),
+            };
+            insert(&mut roots, heading);
+        }
+        Self(roots)
+    }
+
+    /// Whether every heading is exactly one level below the heading it's nested under,
+    /// i.e an h1 followed by an h3 skips a level.
+    pub fn is_valid_hierarchy(&self) -> bool {
+        fn valid(headings: &[Heading]) -> bool {
+            headings.iter().all(|heading| {
+                heading
+                    .children
+                    .iter()
+                    .all(|child| child.level == heading.level + 1)
+                    && valid(&heading.children)
+            })
+        }
+        valid(&self.0)
+    }
+}
+
+/// Nest the heading under the last heading of a lower level, or add it at the top.
+fn insert(headings: &mut Vec<Heading>, heading: Heading) {
+    match headings.last_mut() {
+        Some(last) if last.level < heading.level => insert(&mut last.children, heading),
+        _ => headings.push(heading),
+    }
+}
+
+impl fmt::Display for Outline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_headings(
+            f: &mut fmt::Formatter<'_>,
+            headings: &[Heading],
+            depth: usize,
+        ) -> fmt::Result {
+            for heading in headings {
+                writeln!(
+                    f,
+                    "{}h{} {}",
+                    "  ".repeat(depth),
+                    heading.level,
+                    heading.text
+                )?;
+                write_headings(f, &heading.children, depth + 1)?;
+            }
+            Ok(())
+        }
+        write_headings(f, &self.0, 0)
+    }
+}
+
+#[cfg(all(test, feature = "unit_tests"))]
+pub mod test {
+    use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+
+    fn outline_of(mounted: &Mounted) -> Outline {
+        let elements = mounted.query_selector_all("*").unwrap();
+        Outline::from_elements(
+            (0..elements.length())
+                .filter_map(|i| elements.item(i))
+                .map(|node| node.unchecked_into())
+                .collect(),
+        )
+    }
+
+    #[wasm_bindgen_test]
+    pub fn headings_nest_by_level() {
+        let mounted = Mounted::new(
+            "<h1>Products</h1><h2>Shoes</h2><h3>Boots</h3><h2 hidden>Hidden</h2>\
+            <div role='heading' aria-level='2'>Hats</div>",
+        );
+        let outline = outline_of(&mounted);
+        assert_eq!(
+            outline.to_string(),
+            "h1 Products\n  h2 Shoes\n    h3 Boots\n  h2 Hats\n"
+        );
+        assert!(outline.is_valid_hierarchy());
+    }
+
+    #[wasm_bindgen_test]
+    pub fn skipped_levels_are_invalid() {
+        let mounted = Mounted::new("<h1>Products</h1><h3>Boots</h3>");
+        let outline = outline_of(&mounted);
+        assert!(!outline.is_valid_hierarchy());
+    }
+}
@@ -0,0 +1,40 @@
+use crate::query_root::Scope;
+use crate::role::is_inaccessible;
+use crate::{get_config, Normalizer, TestElement};
+
+/// Options shared by every query, pass them to the `_with` version of a query,
+/// i.e `get_by_text_with("Save", QueryOptions { normalizer: Some(Normalizer::new(|text| text.to_uppercase())), ..Default::default() })`.
+#[derive(Clone, Debug, Default)]
+pub struct QueryOptions {
+    /// Normalizes the text of an element before it's matched, None uses the normalizer of the global `Config`.
+    pub normalizer: Option<Normalizer>,
+    /// Include elements users can't perceive, i.e display:none, visibility:hidden, inside a hidden or inert subtree,
+    /// or aria-hidden="true". They're left out by default.
+    pub hidden: bool,
+    /// Search inside open shadow roots too, slotted content is visited where it's rendered, in flattened tree order.
+    pub pierce_shadow_dom: bool,
+    /// Search inside the documents of same-origin iframes too, their elements follow the iframe.
+    pub pierce_iframes: bool,
+}
+
+impl QueryOptions {
+    /// Normalize text with the normalizer of these options, or the configured one.
+    pub(crate) fn normalize(&self, text: &str) -> String {
+        match &self.normalizer {
+            Some(normalizer) => normalizer.normalize(text),
+            None => get_config().normalizer.normalize(text),
+        }
+    }
+
+    /// Whether the element can be returned by a query with these options, hidden elements only when asked for.
+    pub(crate) fn includes(&self, element: &TestElement) -> bool {
+        self.hidden || !is_inaccessible(element)
+    }
+
+    pub(crate) fn scope(&self) -> Scope {
+        Scope {
+            pierce_shadow_dom: self.pierce_shadow_dom,
+            pierce_iframes: self.pierce_iframes,
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use crate::cast;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Document, Element, Node};
+
+/// How far below the root a query searches, see `QueryOptions::pierce_shadow_dom` and `QueryOptions::pierce_iframes`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Scope {
+    pub(crate) pierce_shadow_dom: bool,
+    pub(crate) pierce_iframes: bool,
+}
+
+/// The part of the DOM a query searches, the whole document or the subtree under one element.
+#[derive(Clone, Debug)]
+pub(crate) enum QueryRoot {
+    Document(Document),
+    Element(Element),
+}
+
+impl QueryRoot {
+    /// Every element under the root, in document order.
+    /// When piercing the shadow DOM, in flattened tree order instead, see `flat_children`.
+    pub(crate) fn all_elements(&self, scope: Scope) -> Vec<Element> {
+        self.query_selector_all("*", scope)
+    }
+
+    /// Elements under the root matching the selector, in document order.
+    /// When piercing shadow roots or iframes, the selector is matched against each element as they're walked,
+    /// the elements of an iframe's document follow the iframe.
+    pub(crate) fn query_selector_all(&self, selector: &str, scope: Scope) -> Vec<Element> {
+        self.try_query_selector_all(selector, scope).unwrap()
+    }
+
+    /// See query_selector_all, fails when the selector isn't valid.
+    pub(crate) fn try_query_selector_all(
+        &self,
+        selector: &str,
+        scope: Scope,
+    ) -> Result<Vec<Element>, JsValue> {
+        if scope != Scope::default() {
+            // Walking only matches the selector against elements, so an empty root would never reject an invalid one.
+            match self {
+                Self::Document(document) => document.query_selector(selector),
+                Self::Element(element) => element.query_selector(selector),
+            }?;
+            let root: Node = match self {
+                Self::Document(document) => document.clone().into(),
+                Self::Element(element) => element.clone().into(),
+            };
+            let mut list = Vec::new();
+            push_descendants(&root, &mut list, scope);
+            let mut matching = Vec::new();
+            for element in list {
+                if element.matches(selector)? {
+                    matching.push(element);
+                }
+            }
+            return Ok(matching);
+        }
+        let list = match self {
+            Self::Document(document) => document.query_selector_all(selector),
+            Self::Element(element) => element.query_selector_all(selector),
+        }?;
+        Ok((0..list.length())
+            .filter_map(|i| list.item(i))
+            .filter_map(|node| cast::element(&node).cloned())
+            .collect())
+    }
+
+    /// The node text is searched under, the body of a document or the element itself.
+    pub(crate) fn text_root(&self) -> Node {
+        match self {
+            Self::Document(document) => {
+                document.body().expect("Document should have a body").into()
+            }
+            Self::Element(element) => element.clone().into(),
+        }
+    }
+}
+
+/// The children of a node as they're rendered, i.e the flattened tree.
+/// A host with an open shadow root renders its shadow root's children instead of its own,
+/// and a <slot> renders the nodes assigned to it, or its own children as a fallback when nothing is assigned.
+pub(crate) fn flat_children(node: &Node) -> Vec<Node> {
+    if let Some(element) = cast::element(node) {
+        if let Some(shadow_root) = element.shadow_root() {
+            return node_list(&shadow_root.child_nodes());
+        }
+        if let Some(slot) = cast::html_tag::<web_sys::HtmlSlotElement>(element, "slot") {
+            let mut options = web_sys::AssignedNodesOptions::new();
+            options.flatten(true);
+            let assigned = slot.assigned_nodes_with_options(&options);
+            if assigned.length() > 0 {
+                return assigned
+                    .iter()
+                    .map(|node| node.unchecked_into::<Node>())
+                    .collect();
+            }
+        }
+    }
+    node_list(&node.child_nodes())
+}
+
+/// The children of a node, flattened when piercing the shadow DOM.
+pub(crate) fn children(node: &Node, scope: Scope) -> Vec<Node> {
+    if scope.pierce_shadow_dom {
+        flat_children(node)
+    } else {
+        node_list(&node.child_nodes())
+    }
+}
+
+/// The document of a same-origin iframe, None for other elements and cross-origin iframes.
+pub(crate) fn frame_document(element: &Element) -> Option<Document> {
+    cast::html_tag::<web_sys::HtmlIFrameElement>(element, "iframe")?.content_document()
+}
+
+/// The element with the id in the element's own tree, its document or the shadow root it's in,
+/// which is where ids referenced by for= and aria-labelledby resolve.
+pub(crate) fn element_by_id(element: &Element, id: &str) -> Option<Element> {
+    match tree_of(element)? {
+        Tree::Document(document) => document.get_element_by_id(id),
+        Tree::ShadowRoot(shadow_root) => shadow_root.get_element_by_id(id),
+    }
+}
+
+/// The elements matching the selector in the element's own tree, see element_by_id.
+pub(crate) fn tree_query_selector_all(element: &Element, selector: &str) -> Vec<Element> {
+    let list = match tree_of(element) {
+        Some(Tree::Document(document)) => document.query_selector_all(selector),
+        Some(Tree::ShadowRoot(shadow_root)) => shadow_root.query_selector_all(selector),
+        None => return Vec::new(),
+    };
+    list.map(|list| {
+        node_list(&list)
+            .iter()
+            .filter_map(|node| cast::element(node).cloned())
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+enum Tree {
+    Document(Document),
+    ShadowRoot(web_sys::ShadowRoot),
+}
+
+/// The root of the tree the element is in, None for an element that isn't attached to a document or shadow root.
+fn tree_of(element: &Element) -> Option<Tree> {
+    let root = element.get_root_node();
+    match root.node_type() {
+        Node::DOCUMENT_NODE => Some(Tree::Document(root.unchecked_into())),
+        Node::DOCUMENT_FRAGMENT_NODE
+            if js_sys::Reflect::has(&root, &"host".into()).unwrap_or(false) =>
+        {
+            Some(Tree::ShadowRoot(root.unchecked_into()))
+        }
+        _ => None,
+    }
+}
+
+fn push_descendants(node: &Node, list: &mut Vec<Element>, scope: Scope) {
+    for child in children(node, scope) {
+        if let Some(element) = cast::element(&child) {
+            list.push(element.clone());
+            if scope.pierce_iframes {
+                if let Some(document) = frame_document(element) {
+                    push_descendants(&document, list, scope);
+                }
+            }
+            push_descendants(&child, list, scope);
+        }
+    }
+}
+
+fn node_list(list: &web_sys::NodeList) -> Vec<Node> {
+    (0..list.length()).filter_map(|i| list.item(i)).collect()
+}
+
+#[cfg(all(test, feature = "unit_tests"))]
+pub mod test {
+    use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    pub fn flattened_tree_renders_slotted_content_in_the_shadow_root() {
+        let mounted = Mounted::new("<div></div>");
+        let host = mounted.first();
+        host.set_inner_html(
+            "<span slot='title' id='shadow_slotted'></span><p id='shadow_unslotted'></p>",
+        );
+        let shadow_root = host
+            .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+            .unwrap();
+        shadow_root.set_inner_html("<header id='shadow_header'><slot name='title'></slot></header><footer id='shadow_footer'></footer>");
+        let root = QueryRoot::Element(host);
+        assert!(root
+            .all_elements(Scope::default())
+            .iter()
+            .all(|e| !e.id().starts_with("shadow_header")));
+        let ids = root
+            .all_elements(Scope {
+                pierce_shadow_dom: true,
+                ..Default::default()
+            })
+            .iter()
+            .map(|e| e.id())
+            .filter(|id| !id.is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec!["shadow_header", "shadow_slotted", "shadow_footer"]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn invalid_selectors_fail_without_any_element_to_match() {
+        let mounted = Mounted::new("");
+        let root = QueryRoot::Element((*mounted).clone());
+        let pierce = Scope {
+            pierce_shadow_dom: true,
+            ..Default::default()
+        };
+        assert!(root.try_query_selector_all("li[", pierce).is_err());
+        assert!(root
+            .try_query_selector_all("li", pierce)
+            .unwrap()
+            .is_empty());
+    }
+}
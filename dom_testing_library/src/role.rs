@@ -0,0 +1,582 @@
+use crate::accname::{accessible_description, accessible_name};
+use crate::cast;
+use crate::query_root::{tree_query_selector_all, Scope};
+use crate::{QueryOptions, TestElement, TextMatch};
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+/// Options that narrow down a role query by the accessible name, description, states and properties of the element.
+/// Fields left as None don't constrain the query, i.e
+/// `RoleOptions { name: Some("Save".into()), ..Default::default() }` finds the button named Save.
+#[derive(Clone, Debug, Default)]
+pub struct RoleOptions {
+    /// The accessible name of the element must match.
+    pub name: Option<TextMatch>,
+    /// The accessible description of the element must match.
+    pub description: Option<TextMatch>,
+    /// aria-checked, or the checkedness of native checkboxes and radios.
+    pub checked: Option<bool>,
+    /// aria-selected, or the selectedness of native options.
+    pub selected: Option<bool>,
+    /// aria-pressed
+    pub pressed: Option<bool>,
+    /// aria-expanded
+    pub expanded: Option<bool>,
+    /// The heading level, from aria-level or the h1-h6 tag.
+    pub level: Option<u32>,
+    /// The aria-current token, i.e "page" or "true". Elements without aria-current have the token "false".
+    pub current: Option<String>,
+    /// aria-busy
+    pub busy: Option<bool>,
+    /// The options every query takes, i.e `hidden` to include elements excluded from the accessibility tree,
+    /// or `pierce_shadow_dom`. The normalizer applies to the accessible name and description.
+    pub query: QueryOptions,
+}
+
+impl RoleOptions {
+    pub(crate) fn scope(&self) -> Scope {
+        self.query.scope()
+    }
+    pub(crate) fn matches(&self, test_element: &TestElement) -> bool {
+        let element: &Element = &test_element.0;
+        if !self.query.includes(test_element) {
+            return false;
+        }
+        if let Some(checked) = self.checked {
+            if is_checked(element) != Some(checked) {
+                return false;
+            }
+        }
+        if let Some(selected) = self.selected {
+            if is_selected(element) != Some(selected) {
+                return false;
+            }
+        }
+        if let Some(pressed) = self.pressed {
+            if aria_state(element, "aria-pressed") != Some(pressed) {
+                return false;
+            }
+        }
+        if let Some(expanded) = self.expanded {
+            if aria_state(element, "aria-expanded") != Some(expanded) {
+                return false;
+            }
+        }
+        if let Some(busy) = self.busy {
+            if aria_state(element, "aria-busy") != Some(busy) {
+                return false;
+            }
+        }
+        if let Some(level) = self.level {
+            if heading_level(element) != Some(level) {
+                return false;
+            }
+        }
+        if let Some(current) = &self.current {
+            let token = element
+                .get_attribute("aria-current")
+                .unwrap_or_else(|| String::from("false"));
+            if &token != current {
+                return false;
+            }
+        }
+        if let Some(name) = &self.name {
+            if !name.matches(
+                &self.query.normalize(&accessible_name(element)),
+                test_element,
+            ) {
+                return false;
+            }
+        }
+        if let Some(description) = &self.description {
+            if !description.matches(
+                &self.query.normalize(&accessible_description(element)),
+                test_element,
+            ) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Sectioning content and landmark ancestors that strip `<header>` and `<footer>` of their landmark roles.
+const SECTIONING_ANCESTORS: &str = "article, aside, main, nav, section, \
+    [role='article'], [role='complementary'], [role='main'], [role='navigation'], [role='region']";
+
+/// The role of an element, the first token of an explicit `role` attribute wins,
+/// otherwise we fall back to the implicit role the HTML-AAM maps the element to.
+pub(crate) fn element_role(element: &Element) -> Option<String> {
+    explicit_role(element).or_else(|| implicit_role(element).map(str::to_string))
+}
+
+fn explicit_role(element: &Element) -> Option<String> {
+    element
+        .get_attribute("role")?
+        .split_whitespace()
+        .next()
+        .map(str::to_lowercase)
+}
+
+/// The implicit ARIA role of an element as defined by https://www.w3.org/TR/html-aam-1.0/#html-element-role-mappings
+/// Elements with no corresponding role return None.
+pub(crate) fn implicit_role(element: &Element) -> Option<&'static str> {
+    let tag = element.tag_name().to_lowercase();
+    let role = match tag.as_str() {
+        "a" | "area" => {
+            if element.has_attribute("href") {
+                "link"
+            } else {
+                "generic"
+            }
+        }
+        "article" => "article",
+        "aside" => {
+            if !is_within_sectioning_content(element) || has_author_name(element) {
+                "complementary"
+            } else {
+                "generic"
+            }
+        }
+        "b" | "bdi" | "bdo" | "body" | "data" | "div" | "i" | "pre" | "q" | "samp" | "small"
+        | "span" | "u" => "generic",
+        "blockquote" => "blockquote",
+        "button" => "button",
+        "caption" => "caption",
+        "code" => "code",
+        "datalist" => "listbox",
+        "dd" => "definition",
+        "del" | "s" => "deletion",
+        "details" | "fieldset" | "optgroup" | "address" | "hgroup" => "group",
+        "dfn" | "dt" => "term",
+        "dialog" => "dialog",
+        "em" => "emphasis",
+        "figure" => "figure",
+        "footer" => {
+            if is_within_sectioning_content(element) {
+                "generic"
+            } else {
+                "contentinfo"
+            }
+        }
+        "form" if has_author_name(element) => "form",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "header" => {
+            if is_within_sectioning_content(element) {
+                "generic"
+            } else {
+                "banner"
+            }
+        }
+        "hr" => "separator",
+        "html" => "document",
+        "img" => match element.get_attribute("alt") {
+            Some(alt) if alt.is_empty() && !has_author_name(element) => "presentation",
+            _ => "img",
+        },
+        "input" => return input_role(element),
+        "ins" => "insertion",
+        "li" => "listitem",
+        "main" => "main",
+        "mark" => "mark",
+        "math" => "math",
+        "menu" | "ol" | "ul" => "list",
+        "meter" => "meter",
+        "nav" => "navigation",
+        "option" if is_within(element, "select, datalist") => "option",
+        "output" => "status",
+        "p" => "paragraph",
+        "progress" => "progressbar",
+        "search" => "search",
+        "section" => {
+            if has_author_name(element) {
+                "region"
+            } else {
+                "generic"
+            }
+        }
+        "select" => {
+            let size = element
+                .get_attribute("size")
+                .and_then(|size| size.trim().parse::<u32>().ok())
+                .unwrap_or(0);
+            if element.has_attribute("multiple") || size > 1 {
+                "listbox"
+            } else {
+                "combobox"
+            }
+        }
+        "strong" => "strong",
+        "sub" => "subscript",
+        "sup" => "superscript",
+        "svg" => "graphics-document",
+        "table" => "table",
+        "tbody" | "thead" | "tfoot" => "rowgroup",
+        "td" => {
+            if is_in_grid(element) {
+                "gridcell"
+            } else {
+                "cell"
+            }
+        }
+        "th" => th_role(element),
+        "textarea" => "textbox",
+        "time" => "time",
+        "tr" => "row",
+        _ => return None,
+    };
+    Some(role)
+}
+
+fn input_role(element: &Element) -> Option<&'static str> {
+    let input_type = element
+        .get_attribute("type")
+        .map(|t| t.trim().to_lowercase())
+        .unwrap_or_default();
+    let has_list = element.has_attribute("list");
+    let role = match input_type.as_str() {
+        "button" | "image" | "reset" | "submit" => "button",
+        "checkbox" => "checkbox",
+        "radio" => "radio",
+        "range" => "slider",
+        "number" => "spinbutton",
+        "search" if has_list => "combobox",
+        "search" => "searchbox",
+        "color" | "date" | "datetime-local" | "file" | "hidden" | "month" | "password" | "time"
+        | "week" => return None,
+        // Unknown and invalid types fall back to a text input.
+        _ if has_list => "combobox",
+        _ => "textbox",
+    };
+    Some(role)
+}
+
+fn th_role(element: &Element) -> &'static str {
+    match element
+        .get_attribute("scope")
+        .map(|scope| scope.to_lowercase())
+        .as_deref()
+    {
+        Some("row") | Some("rowgroup") => return "rowheader",
+        Some("col") | Some("colgroup") => return "columnheader",
+        _ => {}
+    }
+    if element.closest("thead").ok().flatten().is_some() {
+        return "columnheader";
+    }
+    // A header cell sharing its row with data cells labels that row.
+    let row_has_data_cells = element
+        .parent_element()
+        .and_then(|row| row.query_selector(":scope > td").ok().flatten())
+        .is_some();
+    if row_has_data_cells {
+        "rowheader"
+    } else {
+        "columnheader"
+    }
+}
+
+fn is_in_grid(element: &Element) -> bool {
+    element
+        .closest("table")
+        .ok()
+        .flatten()
+        .and_then(|table| explicit_role(&table))
+        .map(|role| role == "grid" || role == "treegrid")
+        .unwrap_or(false)
+}
+
+fn is_within_sectioning_content(element: &Element) -> bool {
+    is_within(element, SECTIONING_ANCESTORS)
+}
+
+/// Whether any ancestor of the element, excluding itself, matches the selector.
+fn is_within(element: &Element, selector: &str) -> bool {
+    element
+        .parent_element()
+        .and_then(|parent| parent.closest(selector).ok().flatten())
+        .is_some()
+}
+
+/// Whether the author gave the element a name, several elements only expose their landmark role when named.
+fn has_author_name(element: &Element) -> bool {
+    ["aria-label", "aria-labelledby", "title"]
+        .iter()
+        .any(|attr| {
+            element
+                .get_attribute(attr)
+                .map(|value| !value.trim().is_empty())
+                .unwrap_or(false)
+        })
+}
+
+/// The state an aria attribute carries, None when it's absent or not a boolean i.e "mixed",
+/// so a state filter only matches elements that have the state.
+fn aria_state(element: &Element, attr: &str) -> Option<bool> {
+    match element.get_attribute(attr).as_deref() {
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        _ => None,
+    }
+}
+
+/// Native checkboxes and radios report their checkedness, an indeterminate checkbox is neither checked nor unchecked.
+/// Other elements are only checkable through aria-checked.
+fn is_checked(element: &Element) -> Option<bool> {
+    if let Some(input) = cast::html_tag::<web_sys::HtmlInputElement>(element, "input") {
+        let input_type = input.type_();
+        if input_type == "checkbox" || input_type == "radio" {
+            if input.indeterminate() {
+                return None;
+            }
+            return Some(input.checked());
+        }
+    }
+    aria_state(element, "aria-checked")
+}
+
+fn is_selected(element: &Element) -> Option<bool> {
+    if let Some(option) = cast::html_tag::<web_sys::HtmlOptionElement>(element, "option") {
+        return Some(option.selected());
+    }
+    aria_state(element, "aria-selected")
+}
+
+pub(crate) fn heading_level(element: &Element) -> Option<u32> {
+    if let Some(level) = element
+        .get_attribute("aria-level")
+        .and_then(|level| level.trim().parse().ok())
+    {
+        return Some(level);
+    }
+    match element.tag_name().to_lowercase().as_str() {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// An element is excluded from the accessibility tree if it or an ancestor is hidden from rendering, inert or marked aria-hidden.
+pub(crate) fn is_inaccessible(element: &Element) -> bool {
+    if is_hidden(element) {
+        return true;
+    }
+    let mut current = element.parent_element().or_else(|| outer_parent(element));
+    while let Some(element) = current {
+        if hides_subtree(&element) {
+            return true;
+        }
+        current = element.parent_element().or_else(|| outer_parent(&element));
+    }
+    false
+}
+
+/// Whether this element, on its own, is hidden from the accessibility tree.
+pub(crate) fn is_hidden(element: &Element) -> bool {
+    // visibility is inherited, so only the element itself needs checking.
+    hides_subtree(element)
+        || computed_style(element, None)
+            .map(|style| style.get_property_value("visibility").as_deref() == Ok("hidden"))
+            .unwrap_or(false)
+}
+
+/// Whether the element hides itself and everything inside of it.
+fn hides_subtree(element: &Element) -> bool {
+    element.has_attribute("hidden")
+        || element.has_attribute("inert")
+        || element.get_attribute("aria-hidden").as_deref() == Some("true")
+        || (computed_style(element, None)
+            .map(|style| style.get_property_value("display").as_deref() == Ok("none"))
+            .unwrap_or(false)
+            && !is_mapped_area(element))
+}
+
+/// An <area> is never rendered itself, it's shown through the visible <img> that uses its <map>.
+fn is_mapped_area(element: &Element) -> bool {
+    if element.local_name() != "area" || cast::html_element(element).is_none() {
+        return false;
+    }
+    let Some(name) = element
+        .closest("map")
+        .ok()
+        .flatten()
+        .and_then(|map| map.get_attribute("name"))
+    else {
+        return false;
+    };
+    tree_query_selector_all(element, "img[usemap]")
+        .into_iter()
+        .any(|image| {
+            image
+                .get_attribute("usemap")
+                .is_some_and(|usemap| usemap.strip_prefix('#') == Some(name.as_str()))
+                && !is_inaccessible(&image)
+        })
+}
+
+/// The computed style of the element or one of its pseudo elements in its own window,
+/// which is the iframe's window for frame documents.
+pub(crate) fn computed_style(
+    element: &Element,
+    pseudo: Option<&str>,
+) -> Option<web_sys::CssStyleDeclaration> {
+    let window = element.owner_document()?.default_view()?;
+    match pseudo {
+        Some(pseudo) => window.get_computed_style_with_pseudo_elt(element, pseudo),
+        None => window.get_computed_style(element),
+    }
+    .ok()
+    .flatten()
+}
+
+/// The element an element without a parent element is rendered inside of,
+/// the host for the top of a shadow tree or the iframe for the root of a frame document.
+fn outer_parent(element: &Element) -> Option<Element> {
+    let parent = element.parent_node()?;
+    match parent.node_type() {
+        web_sys::Node::DOCUMENT_FRAGMENT_NODE => js_sys::Reflect::get(&parent, &"host".into())
+            .ok()
+            .filter(|host| host.is_object())
+            .map(|host| host.unchecked_into()),
+        web_sys::Node::DOCUMENT_NODE => element
+            .owner_document()?
+            .default_view()?
+            .frame_element()
+            .ok()
+            .flatten(),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "unit_tests"))]
+pub mod test {
+    use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    pub fn implicit_roles() {
+        assert_eq!(
+            implicit_role(&Mounted::new("<button>Ok</button>").first()),
+            Some("button")
+        );
+        assert_eq!(
+            implicit_role(&Mounted::new("<a href='/'>Home</a>").first()),
+            Some("link")
+        );
+        assert_eq!(
+            implicit_role(&Mounted::new("<input type='checkbox'/>").first()),
+            Some("checkbox")
+        );
+        assert_eq!(
+            implicit_role(&Mounted::new("<input list='l'/>").first()),
+            Some("combobox")
+        );
+        assert_eq!(
+            implicit_role(&Mounted::new("<input type='bogus'/>").first()),
+            Some("textbox")
+        );
+        assert_eq!(
+            implicit_role(&Mounted::new("<input type='password'/>").first()),
+            None
+        );
+        assert_eq!(
+            implicit_role(&Mounted::new("<h2>Title</h2>").first()),
+            Some("heading")
+        );
+        assert_eq!(
+            implicit_role(&Mounted::new("<nav></nav>").first()),
+            Some("navigation")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn context_dependent_roles() {
+        assert_eq!(
+            implicit_role(&Mounted::new("<header></header>").first()),
+            Some("banner")
+        );
+        let mounted = Mounted::new("<article><header></header></article>");
+        let header = mounted.first().first_element_child().unwrap();
+        assert_eq!(implicit_role(&header), Some("generic"));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn role_options() {
+        let mounted = Mounted::new("<h3 aria-expanded='true'>Section</h3>");
+        let heading = TestElement(mounted.first());
+        let options = RoleOptions {
+            name: Some(TextMatch::from("Section")),
+            level: Some(3),
+            expanded: Some(true),
+            ..Default::default()
+        };
+        assert!(options.matches(&heading));
+        let options = RoleOptions {
+            level: Some(2),
+            ..Default::default()
+        };
+        assert!(!options.matches(&heading));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn state_filters_need_the_state() {
+        let mounted = Mounted::new(
+            "<button>Plain</button><button aria-pressed='false' aria-expanded='false'>Toggle</button>\
+            <input type='checkbox'/><div role='checkbox' aria-checked='false'></div>",
+        );
+        let elements = mounted.query_selector_all("*").unwrap();
+        let matching = |options: RoleOptions| {
+            (0..elements.length())
+                .map(|i| TestElement(elements.get(i).unwrap().unchecked_into()))
+                .filter(|element| options.matches(element))
+                .count()
+        };
+        let unpressed = RoleOptions {
+            pressed: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(matching(unpressed), 1);
+        let collapsed = RoleOptions {
+            expanded: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(matching(collapsed), 1);
+        let unchecked = RoleOptions {
+            checked: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(matching(unchecked), 2);
+        let idle = RoleOptions {
+            busy: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(matching(idle), 0);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn role_options_exclude_hidden() {
+        let mounted = Mounted::new("<button aria-hidden='true'>Hidden</button>");
+        let button = TestElement(mounted.first());
+        assert!(!RoleOptions::default().matches(&button));
+        let options = RoleOptions {
+            query: QueryOptions {
+                hidden: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(options.matches(&button));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn explicit_role_wins() {
+        let mounted = Mounted::new("<div role='tab button'></div>");
+        let element = mounted.first();
+        assert_eq!(element_role(&element), Some(String::from("tab")));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::role::element_role;
+use crate::{cast, get_config, get_one_inner, GetOneError, TestElement, TextMatch};
+use web_sys::Element;
+
+const TABLE_ROLES: &[&str] = &["table", "grid", "treegrid"];
+const CELL_ROLES: &[&str] = &["cell", "gridcell", "columnheader", "rowheader"];
+
+/// The cells of a <table>, or an element with role table, grid or treegrid, laid out the way they're rendered.
+/// A cell spanning several rows or columns fills every slot it covers, nested tables are left to themselves.
+pub(crate) struct Table {
+    rows: Vec<Element>,
+    grid: Vec<Vec<Option<Element>>>,
+}
+
+impl Table {
+    /// None if the element isn't a table.
+    pub(crate) fn new(table: &Element) -> Option<Self> {
+        if !TABLE_ROLES.contains(&element_role(table)?.as_str()) {
+            return None;
+        }
+        let rows = descendants(table)
+            .into_iter()
+            .filter(|row| element_role(row).as_deref() == Some("row"))
+            .filter(|row| closest_with_role(row, TABLE_ROLES).as_ref() == Some(table))
+            .collect::<Vec<_>>();
+        let mut grid: Vec<Vec<Option<Element>>> = vec![Vec::new(); rows.len()];
+        for (row_index, row) in rows.iter().enumerate() {
+            let mut column = 0;
+            for cell in cells_of(row) {
+                while grid[row_index].get(column).is_some_and(Option::is_some) {
+                    column += 1;
+                }
+                let row_span = match row_span(&cell) {
+                    // A row span of 0 reaches the last row of the row group.
+                    0 => rows[row_index..]
+                        .iter()
+                        .take_while(|other| other.parent_element() == row.parent_element())
+                        .count(),
+                    row_span => row_span,
+                };
+                let column_span = column_span(&cell);
+                for spanned_row in grid.iter_mut().skip(row_index).take(row_span) {
+                    if spanned_row.len() < column + column_span {
+                        spanned_row.resize(column + column_span, None);
+                    }
+                    for slot in &mut spanned_row[column..column + column_span] {
+                        *slot = Some(cell.clone());
+                    }
+                }
+                column += column_span;
+            }
+        }
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        for row in grid.iter_mut() {
+            row.resize(width, None);
+        }
+        Some(Self { rows, grid })
+    }
+
+    /// The normalized text of every slot, empty for slots no cell covers.
+    pub(crate) fn to_grid(&self) -> Vec<Vec<String>> {
+        self.grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.as_ref().map(text).unwrap_or_default())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The cell at the crossing of the row and column whose headers match.
+    /// A row is matched by its rowheader cells, or its first cell, a column by its columnheader cells, or its first row.
+    /// Headers matching several rows or columns that cross at different cells are reported as more than one.
+    pub(crate) fn cell(
+        &self,
+        row_header: &TextMatch,
+        column_header: &TextMatch,
+    ) -> Result<TestElement, GetOneError> {
+        let width = self.grid.first().map_or(0, Vec::len);
+        let rows = (0..self.grid.len()).filter(|&row| {
+            (0..width).any(|column| {
+                self.grid[row][column].as_ref().is_some_and(|cell| {
+                    (column == 0 || element_role(cell).as_deref() == Some("rowheader"))
+                        && matches(row_header, cell)
+                })
+            })
+        });
+        let columns = (0..width)
+            .filter(|&column| {
+                (0..self.grid.len()).any(|row| {
+                    self.grid[row][column].as_ref().is_some_and(|cell| {
+                        (row == 0 || element_role(cell).as_deref() == Some("columnheader"))
+                            && matches(column_header, cell)
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut cells: Vec<TestElement> = Vec::new();
+        for row in rows {
+            for &column in &columns {
+                if let Some(cell) = &self.grid[row][column] {
+                    let cell = TestElement(cell.clone());
+                    if !cells.contains(&cell) {
+                        cells.push(cell);
+                    }
+                }
+            }
+        }
+        get_one_inner(
+            cells,
+            "cell",
+            format!("row {row_header}, column {column_header}"),
+        )
+    }
+
+    /// The rows with a cell whose text matches.
+    pub(crate) fn rows_by_text(&self, text: &TextMatch) -> Vec<TestElement> {
+        self.rows
+            .iter()
+            .zip(self.grid.iter())
+            .filter(|(_, cells)| cells.iter().flatten().any(|cell| matches(text, cell)))
+            .map(|(row, _)| TestElement(row.clone()))
+            .collect()
+    }
+}
+
+fn matches(text_match: &TextMatch, cell: &Element) -> bool {
+    let cell = TestElement(cell.clone());
+    text_match.matches(&text(&cell), &cell)
+}
+
+fn text(cell: &Element) -> String {
+    get_config()
+        .normalizer
+        .normalize(&TestElement(cell.clone()).inner_text())
+}
+
+/// The cells of a row, leaving out the cells of tables nested in it.
+fn cells_of(row: &Element) -> Vec<Element> {
+    descendants(row)
+        .into_iter()
+        .filter(|cell| element_role(cell).is_some_and(|role| CELL_ROLES.contains(&role.as_str())))
+        .filter(|cell| closest_with_role(cell, &["row"]).as_ref() == Some(row))
+        .collect()
+}
+
+/// The most columns and rows a cell can span, the limits browsers clamp colspan and rowspan to.
+const MAX_COLUMN_SPAN: usize = 1000;
+const MAX_ROW_SPAN: usize = 65534;
+
+/// How many columns a cell spans, from colspan on td and th as the browser reads it or aria-colspan otherwise.
+fn column_span(cell: &Element) -> usize {
+    match native_cell(cell) {
+        Some(cell) => cell.col_span() as usize,
+        None => aria_span(cell, "aria-colspan").clamp(1, MAX_COLUMN_SPAN),
+    }
+}
+
+/// How many rows a cell spans, the same way as column_span. 0 is kept, it spans the rest of the row group.
+fn row_span(cell: &Element) -> usize {
+    match native_cell(cell) {
+        Some(cell) => cell.row_span() as usize,
+        None => aria_span(cell, "aria-rowspan").min(MAX_ROW_SPAN),
+    }
+}
+
+fn native_cell(cell: &Element) -> Option<&web_sys::HtmlTableCellElement> {
+    cast::html_tag(cell, "td").or_else(|| cast::html_tag(cell, "th"))
+}
+
+fn aria_span(cell: &Element, attribute: &str) -> usize {
+    cell.get_attribute(attribute)
+        .and_then(|span| span.trim().parse::<usize>().ok())
+        .unwrap_or(1)
+}
+
+fn closest_with_role(element: &Element, roles: &[&str]) -> Option<Element> {
+    let mut current = element.parent_element();
+    while let Some(element) = current {
+        if element_role(&element).is_some_and(|role| roles.contains(&role.as_str())) {
+            return Some(element);
+        }
+        current = element.parent_element();
+    }
+    None
+}
+
+fn descendants(element: &Element) -> Vec<Element> {
+    let Ok(list) = element.query_selector_all("*") else {
+        return Vec::new();
+    };
+    (0..list.length())
+        .filter_map(|i| list.item(i))
+        .filter_map(|node| cast::element(&node).cloned())
+        .collect()
+}
+
+#[cfg(all(test, feature = "unit_tests"))]
+pub mod test {
+    use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    pub fn spans_fill_every_slot_they_cover() {
+        let mounted = Mounted::new(
+            "<table><tr><th rowspan='2'>A</th><th colspan='2'>B</th></tr>\
+            <tr><td>C</td><td>D</td></tr></table>",
+        );
+        let table = Table::new(&mounted.first()).unwrap();
+        assert_eq!(
+            table.to_grid(),
+            vec![vec!["A", "B", "B"], vec!["A", "C", "D"]]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn spans_are_clamped_like_the_browser_does() {
+        let mounted = Mounted::new(
+            "<table><tr><td colspan='4000000000'>A</td></tr></table>\
+            <div role='table'><div role='row'><span role='cell' aria-colspan='4000000000'>B</span></div></div>",
+        );
+        let table = Table::new(&mounted.first()).unwrap();
+        assert_eq!(table.to_grid()[0].len(), MAX_COLUMN_SPAN);
+        let aria_table = Table::new(&mounted.first().next_element_sibling().unwrap()).unwrap();
+        assert_eq!(aria_table.to_grid()[0].len(), MAX_COLUMN_SPAN);
+    }
+
+    #[wasm_bindgen_test]
+    pub fn zero_rowspan_reaches_the_end_of_the_row_group() {
+        let mounted = Mounted::new(
+            "<table><tbody><tr><td rowspan='0'>A</td><td>B</td></tr><tr><td>C</td></tr></tbody>\
+            <tbody><tr><td>D</td><td>E</td></tr></tbody></table>",
+        );
+        let table = Table::new(&mounted.first()).unwrap();
+        assert_eq!(
+            table.to_grid(),
+            vec![vec!["A", "B"], vec!["A", "C"], vec!["D", "E"]]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    pub fn ambiguous_headers_are_more_than_one() {
+        let mounted = Mounted::new(
+            "<table><tr><th>Name</th><th>Age</th><th>Age</th></tr>\
+            <tr><td>Ada</td><td>36</td><td>37</td></tr><tr><td>Ada</td><td>40</td><td>41</td></tr></table>",
+        );
+        let table = Table::new(&mounted.first()).unwrap();
+        assert!(matches!(
+            table.cell(&"Ada".into(), &"Name".into()),
+            Err(GetOneError::MoreThanOne { .. })
+        ));
+        assert!(matches!(
+            table.cell(&"Ada".into(), &"Age".into()),
+            Err(GetOneError::MoreThanOne { .. })
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn aria_grids_are_tables() {
+        let mounted = Mounted::new(
+            "<div role='grid'><div role='row'><span role='columnheader'>Name</span><span role='columnheader'>Age</span></div>\
+            <div role='row'><span role='gridcell'>Ada</span><span role='gridcell'>36</span></div></div>",
+        );
+        let table = Table::new(&mounted.first()).unwrap();
+        let age = table.cell(&"Ada".into(), &"Age".into()).unwrap();
+        assert_eq!(age.text_content().unwrap(), "36");
+        assert_eq!(table.rows_by_text(&"Ada".into()).len(), 1);
+    }
+}
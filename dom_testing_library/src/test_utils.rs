@@ -0,0 +1,44 @@
+//! Fixtures shared by the unit tests.
+use std::ops::Deref;
+use web_sys::Element;
+
+/// Html mounted in a div on the body, i.e removed again when dropped so tests never see each other's elements.
+pub(crate) struct Mounted(Element);
+
+impl Mounted {
+    pub(crate) fn new(html: &str) -> Self {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let wrapper = document.create_element("div").unwrap();
+        wrapper.set_inner_html(html);
+        document.body().unwrap().append_child(&wrapper).unwrap();
+        Self(wrapper)
+    }
+    /// Append the element to the head, i.e a <base> or <style>, removed again when dropped.
+    pub(crate) fn in_head(element: Element) -> Self {
+        let document = web_sys::window().unwrap().document().unwrap();
+        document
+            .query_selector("head")
+            .unwrap()
+            .unwrap()
+            .append_child(&element)
+            .unwrap();
+        Self(element)
+    }
+    /// The first element of the mounted html.
+    pub(crate) fn first(&self) -> Element {
+        self.0.first_element_child().unwrap()
+    }
+}
+
+impl Deref for Mounted {
+    type Target = Element;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for Mounted {
+    fn drop(&mut self) {
+        self.0.remove();
+    }
+}
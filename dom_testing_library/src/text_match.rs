@@ -0,0 +1,211 @@
+use crate::TestElement;
+use std::fmt;
+use std::rc::Rc;
+
+/// A closure deciding whether the text of an element matches.
+pub type MatcherFn = Rc<dyn Fn(&str, &TestElement) -> bool>;
+
+/// How a query compares text against the input, every text based query accepts anything that converts into a TextMatch.
+/// Strings match exactly, so `get_by_text("Save")` and `get_by_text(TextMatch::exact("Save"))` are the same query.
+/// i.e `get_by_text(TextMatch::contains("Sav"))`, `get_by_text(Regex::new(r"Items \(\d+\)").unwrap())`
+#[derive(Clone)]
+pub enum TextMatch {
+    /// The text is exactly the input.
+    Exact(String),
+    /// The text contains the input, i.e "abc" contains "b".
+    Contains(String),
+    /// The text is the input, ignoring case.
+    CaseInsensitive(String),
+    /// The text matches the regular expression somewhere, anchor it with ^ and $ to match the whole text.
+    Regex(regex::Regex),
+    /// The closure decides, given the text and the element it belongs to.
+    Function(MatcherFn),
+}
+
+impl TextMatch {
+    pub fn exact<S: Into<String>>(text: S) -> Self {
+        Self::Exact(text.into())
+    }
+    pub fn contains<S: Into<String>>(text: S) -> Self {
+        Self::Contains(text.into())
+    }
+    pub fn case_insensitive<S: Into<String>>(text: S) -> Self {
+        Self::CaseInsensitive(text.into())
+    }
+    pub fn regex(regex: regex::Regex) -> Self {
+        Self::Regex(regex)
+    }
+    pub fn function<F: Fn(&str, &TestElement) -> bool + 'static>(f: F) -> Self {
+        Self::Function(Rc::new(f))
+    }
+
+    /// Whether the text of the element matches.
+    pub fn matches(&self, text: &str, element: &TestElement) -> bool {
+        match self {
+            Self::Exact(expected) => text == expected,
+            Self::Contains(expected) => text.contains(expected.as_str()),
+            Self::CaseInsensitive(expected) => text.to_lowercase() == expected.to_lowercase(),
+            Self::Regex(regex) => regex.is_match(text),
+            Self::Function(f) => f(text, element),
+        }
+    }
+}
+
+impl fmt::Display for TextMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(text) => write!(f, "{text}"),
+            Self::Contains(text) => write!(f, "text containing \"{text}\""),
+            Self::CaseInsensitive(text) => write!(f, "\"{text}\" ignoring case"),
+            Self::Regex(regex) => write!(f, "/{regex}/"),
+            Self::Function(_) => write!(f, "a custom matcher function"),
+        }
+    }
+}
+
+impl fmt::Debug for TextMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(text) => f.debug_tuple("Exact").field(text).finish(),
+            Self::Contains(text) => f.debug_tuple("Contains").field(text).finish(),
+            Self::CaseInsensitive(text) => f.debug_tuple("CaseInsensitive").field(text).finish(),
+            Self::Regex(regex) => f.debug_tuple("Regex").field(regex).finish(),
+            Self::Function(_) => f.write_str("Function(..)"),
+        }
+    }
+}
+
+/// Turns the raw text of an element into the text queries match against.
+/// The default trims the text and collapses runs of whitespace, including newlines and non-breaking spaces, into one space.
+#[derive(Clone)]
+pub struct Normalizer(Rc<dyn Fn(&str) -> String>);
+
+impl Normalizer {
+    pub fn new<F: Fn(&str) -> String + 'static>(f: F) -> Self {
+        Self(Rc::new(f))
+    }
+
+    /// The default normalizer with either step turned off, i.e `Normalizer::with(false, true)` keeps leading whitespace.
+    pub fn with(trim: bool, collapse_whitespace: bool) -> Self {
+        Self::new(move |text| {
+            let text = if collapse_whitespace {
+                collapse_whitespace_runs(text)
+            } else {
+                text.to_string()
+            };
+            if trim {
+                text.trim().to_string()
+            } else {
+                text
+            }
+        })
+    }
+
+    /// Skip normalization, the raw text is matched.
+    pub fn none() -> Self {
+        Self::new(str::to_string)
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        (self.0)(text)
+    }
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self::with(true, true)
+    }
+}
+
+impl fmt::Debug for Normalizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Normalizer(..)")
+    }
+}
+
+fn collapse_whitespace_runs(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(c);
+            in_whitespace = false;
+        }
+    }
+    collapsed
+}
+
+impl From<&str> for TextMatch {
+    fn from(value: &str) -> Self {
+        Self::Exact(value.to_string())
+    }
+}
+
+impl From<String> for TextMatch {
+    fn from(value: String) -> Self {
+        Self::Exact(value)
+    }
+}
+
+impl From<&String> for TextMatch {
+    fn from(value: &String) -> Self {
+        Self::Exact(value.clone())
+    }
+}
+
+impl From<regex::Regex> for TextMatch {
+    fn from(value: regex::Regex) -> Self {
+        Self::Regex(value)
+    }
+}
+
+#[cfg(all(test, feature = "unit_tests"))]
+pub mod test {
+    use super::*;
+    use crate::test_utils::Mounted;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    pub fn every_kind_of_match() {
+        let mounted = Mounted::new("<p id='text_match_element'>Items (3)</p>");
+        let element = TestElement(mounted.first());
+        assert!(TextMatch::exact("Items (3)").matches("Items (3)", &element));
+        assert!(!TextMatch::exact("Items").matches("Items (3)", &element));
+        assert!(TextMatch::contains("(3)").matches("Items (3)", &element));
+        assert!(!TextMatch::contains("(4)").matches("Items (3)", &element));
+        assert!(TextMatch::case_insensitive("ITEMS (3)").matches("items (3)", &element));
+        assert!(!TextMatch::case_insensitive("items").matches("Items (3)", &element));
+        let regex = TextMatch::from(regex::Regex::new(r"Items \(\d+\)").unwrap());
+        assert!(regex.matches("All Items (3)", &element));
+        assert!(!regex.matches("Items (x)", &element));
+        let by_id = TextMatch::function(|text, element| {
+            text.is_empty() && element.id() == "text_match_element"
+        });
+        assert!(by_id.matches("", &element));
+        assert!(!by_id.matches("Items (3)", &element));
+    }
+
+    #[wasm_bindgen_test]
+    pub fn display_describes_the_match() {
+        assert_eq!(TextMatch::exact("Save").to_string(), "Save");
+        assert_eq!(
+            TextMatch::contains("Sav").to_string(),
+            "text containing \"Sav\""
+        );
+        assert_eq!(
+            TextMatch::case_insensitive("save").to_string(),
+            "\"save\" ignoring case"
+        );
+        let regex = regex::Regex::new("^Sa").unwrap();
+        assert_eq!(TextMatch::regex(regex).to_string(), "/^Sa/");
+        assert_eq!(
+            TextMatch::function(|_, _| true).to_string(),
+            "a custom matcher function"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{get_config, GetOneError};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+/// How long the find_by_X series and `wait_for` keep retrying, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WaitForOptions {
+    /// Give up and return the last error after this long, defaults to `Config::async_util_timeout`.
+    pub timeout: u32,
+    /// Retry at least this often, the callback is also retried whenever the DOM changes.
+    pub interval: u32,
+}
+
+impl Default for WaitForOptions {
+    fn default() -> Self {
+        Self {
+            timeout: get_config().async_util_timeout,
+            interval: 50,
+        }
+    }
+}
+
+/// Retry the callback until it succeeds, i.e until the element you're waiting for has rendered.
+/// It runs once straight away, then after every DOM mutation and every `interval` milliseconds as a fallback.
+/// Once `timeout` milliseconds pass it fails with `GetOneError::Timeout` holding the callback's last error.
+pub async fn wait_for<T, F>(mut callback: F, options: WaitForOptions) -> Result<T, GetOneError>
+where
+    F: FnMut() -> Result<T, GetOneError>,
+{
+    let start = js_sys::Date::now();
+    loop {
+        let last_error = match callback() {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        let elapsed = js_sys::Date::now() - start;
+        if elapsed >= options.timeout as f64 {
+            return Err(GetOneError::timeout(options.timeout, last_error));
+        }
+        let remaining = options.timeout as f64 - elapsed;
+        next_dom_change_or_tick(options.interval.min(remaining.ceil() as u32)).await;
+    }
+}
+
+/// Resolves on the next mutation anywhere in the document, or after `interval` milliseconds, whichever is first.
+async fn next_dom_change_or_tick(interval: u32) {
+    let window = web_sys::window().expect("a window to wait in");
+    let document = window.document().expect("the window to have a document");
+    let mut resolve = None;
+    let promise = js_sys::Promise::new(&mut |resolve_fn, _reject| resolve = Some(resolve_fn));
+    let resolve = resolve.expect("the promise executor to run synchronously");
+
+    let on_mutation = Closure::<dyn FnMut()>::new({
+        let resolve = resolve.clone();
+        move || {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    });
+    let observer = web_sys::MutationObserver::new(on_mutation.as_ref().unchecked_ref())
+        .expect("a mutation observer to be constructed");
+    let mut init = web_sys::MutationObserverInit::new();
+    init.child_list(true)
+        .subtree(true)
+        .attributes(true)
+        .character_data(true);
+    observer
+        .observe_with_options(&document, &init)
+        .expect("the document to be observable");
+    let timeout_handle = window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, interval as i32)
+        .expect("a timeout to be set");
+    let _watch = Watch {
+        window,
+        observer,
+        timeout_handle,
+        _on_mutation: on_mutation,
+    };
+
+    let _ = JsFuture::from(promise).await;
+}
+
+/// The observer and timeout of one wait, stopped when dropped, which is also when a find_by future is dropped mid wait.
+/// Otherwise the observer would outlive its closure and every later mutation would call a dropped closure.
+struct Watch {
+    window: web_sys::Window,
+    observer: web_sys::MutationObserver,
+    timeout_handle: i32,
+    _on_mutation: Closure<dyn FnMut()>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+        self.window.clear_timeout_with_handle(self.timeout_handle);
+    }
+}
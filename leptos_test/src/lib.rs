@@ -25,7 +25,7 @@ impl Drop for TestRender {
     }
 }
 impl HoldsDocument for TestRender {
-    fn document(&self) -> DocumentWrapper {
+    fn document(&self) -> DocumentWrapper<'_> {
         DocumentWrapper(&self.document)
     }
 }